@@ -10,5 +10,26 @@ class PointCloud:
     def distances(self) -> np.ndarray:
         return persistence.pairwise_distances(self.points)
 
-    def persistence_intervals(self, max_dim: int, max_dist: float) -> tuple:
-        return persistence.persistence_intervals(self.points, max_dim, max_dim)
+    def persistence_intervals(
+        self,
+        max_dim: int,
+        max_dist: float,
+        representatives: bool = True,
+        representative_threshold: float | None = None,
+    ) -> dict:
+        return persistence.persistence_intervals(
+            self.points, max_dim, max_dist, representatives, representative_threshold
+        )
+
+    def persistence_image(
+        self,
+        dim: int,
+        max_dist: float,
+        resolution: tuple[int, int] = (20, 20),
+        bandwidth: float = 0.1,
+        birth_range: tuple[float, float] | None = None,
+        persistence_range: tuple[float, float] | None = None,
+    ) -> np.ndarray:
+        return persistence.persistence_image(
+            self.points, dim, max_dist, resolution, bandwidth, birth_range, persistence_range
+        )
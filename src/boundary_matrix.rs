@@ -0,0 +1,167 @@
+use ordered_float::OrderedFloat;
+use std::collections::HashSet;
+
+use super::homology::ChainComplex;
+use super::sparse::{Cell, SparseBoundaryMatrix, SparseMatrixError};
+
+/// A generic filtered chain complex given by its boundary matrix, with columns in any order.
+///
+/// This allows computing persistence of complexes that are not simplicial (CW complexes,
+/// Morse complexes, or boundary matrices produced by other tools) without inventing simplices.
+/// The columns are put in filtration order by [`SparseBoundaryMatrix::from_unordered_columns`],
+/// and [`Self::original_index`] maps the chains back to the columns as given.
+#[derive(Debug, Clone)]
+pub struct FilteredBoundaryMatrix {
+    /// Boundary matrix in filtration order
+    matrix: SparseBoundaryMatrix,
+    /// Index of each chain in the columns given to the constructor
+    original_indices: Vec<usize>,
+}
+
+impl FilteredBoundaryMatrix {
+    /// Creates a filtered boundary matrix with coefficients in Z/2.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - Indices of the faces in the boundary of each column
+    /// * `dims` - Dimension of each column
+    /// * `levels` - Filtration level of each column
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the new matrix or an error, as for [`Self::with_coefficients`].
+    pub fn new(columns: Vec<Vec<usize>>, dims: Vec<usize>, levels: Vec<f64>) -> Result<Self, SparseMatrixError> {
+        let columns = columns.into_iter().map(|faces| faces.into_iter().map(|face| (face, 1)).collect()).collect();
+        Self::with_coefficients(columns, dims, levels)
+    }
+
+    /// Creates a filtered boundary matrix with integer coefficients.
+    ///
+    /// Coefficients of repeated faces are summed, so that e.g. the boundary of the 2-cell of the
+    /// projective plane can be given as twice its edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - Oriented boundary of each column, as (face index, coefficient) pairs
+    /// * `dims` - Dimension of each column
+    /// * `levels` - Filtration level of each column
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the new matrix or an error, as for
+    /// [`SparseBoundaryMatrix::from_unordered_columns`].
+    pub fn with_coefficients(
+        columns: Vec<Vec<(usize, i64)>>,
+        dims: Vec<usize>,
+        levels: Vec<f64>,
+    ) -> Result<Self, SparseMatrixError> {
+        let (matrix, original_indices) = SparseBoundaryMatrix::from_unordered_columns(columns, dims, levels)?;
+        Ok(Self { matrix, original_indices })
+    }
+
+    /// Returns the boundary matrix, in filtration order.
+    pub fn matrix(&self) -> &SparseBoundaryMatrix {
+        &self.matrix
+    }
+
+    /// Returns the index, in the columns given to the constructor, of the chain at the given
+    /// index.
+    pub fn original_index(&self, index: usize) -> usize {
+        self.original_indices[index]
+    }
+}
+
+impl ChainComplex<Cell> for FilteredBoundaryMatrix {
+    fn chain(&self, index: usize) -> &Cell {
+        self.matrix.chain(index)
+    }
+
+    fn chains(&self) -> &Vec<Cell> {
+        self.matrix.chains()
+    }
+
+    fn boundary(&self, index: usize) -> HashSet<usize> {
+        self.matrix.boundary(index)
+    }
+
+    fn signed_boundary(&self, index: usize) -> Vec<(usize, i64)> {
+        self.matrix.signed_boundary(index)
+    }
+
+    fn filtration_level(&self, index: usize) -> OrderedFloat<f64> {
+        self.matrix.filtration_level(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::PrimeField;
+    use crate::homology::PersistenceOptions;
+
+    /// CW structure of the projective plane: one vertex, one edge and one 2-cell attached along
+    /// twice the edge, given out of filtration order.
+    fn projective_plane() -> FilteredBoundaryMatrix {
+        FilteredBoundaryMatrix::with_coefficients(
+            vec![vec![(2, 1), (2, 1)], vec![], vec![]],
+            vec![2, 0, 1],
+            vec![2.0, 0.0, 1.0],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_projective_plane() {
+        let complex = projective_plane();
+        assert_eq!(complex.chains().iter().map(|c| c.dim).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!((0..3).map(|ix| complex.original_index(ix)).collect::<Vec<_>>(), vec![1, 2, 0]);
+        assert_eq!(complex.signed_boundary(2), vec![(1, 2)]);
+        assert!(complex.boundary(2).is_empty());
+
+        let homology = complex.integral_homology();
+        assert_eq!(homology[&0].rank, 1);
+        assert_eq!((homology[&1].rank, homology[&1].torsion.clone()), (0, vec![2]));
+        assert_eq!(homology[&2].rank, 0);
+
+        // Over Z/2 the edge and 2-cell are never paired, over Z/3 they are
+        let intervals = complex.persistence_intervals();
+        assert_eq!(intervals[&1][0].death, f64::INFINITY);
+        assert_eq!(intervals[&2].len(), 1);
+        let options = PersistenceOptions { field: PrimeField::new(3).unwrap(), ..Default::default() };
+        let intervals = complex.persistence_intervals_with_options(&options);
+        assert_eq!((intervals[&1][0].birth, intervals[&1][0].death), (1.0, 2.0));
+        assert!(intervals[&2].is_empty());
+    }
+
+    #[test]
+    fn test_torus() {
+        // One vertex, two loops and a square attached along aba^{-1}b^{-1}
+        let complex = FilteredBoundaryMatrix::with_coefficients(
+            vec![vec![], vec![], vec![], vec![(1, 1), (2, 1), (1, -1), (2, -1)]],
+            vec![0, 1, 1, 2],
+            vec![0.0; 4],
+        )
+        .unwrap();
+        let homology = complex.integral_homology();
+        assert_eq!((0..3).map(|dim| homology[&dim].rank).collect::<Vec<_>>(), vec![1, 2, 1]);
+        assert!(homology.values().all(|group| group.torsion.is_empty()));
+    }
+
+    #[test]
+    fn test_invalid_matrices() {
+        let result = FilteredBoundaryMatrix::new(vec![vec![]], vec![0, 0], vec![0.0]);
+        assert!(matches!(result, Err(SparseMatrixError::LengthMismatch { .. })));
+
+        let result = FilteredBoundaryMatrix::new(vec![vec![], vec![2]], vec![0, 1], vec![0.0, 0.0]);
+        assert!(matches!(result, Err(SparseMatrixError::InvalidRow { row: 2, column: 1 })));
+
+        let result = FilteredBoundaryMatrix::new(vec![vec![], vec![0]], vec![0, 2], vec![0.0, 0.0]);
+        assert!(matches!(result, Err(SparseMatrixError::InvalidDimension { .. })));
+
+        let result = FilteredBoundaryMatrix::new(vec![vec![], vec![0]], vec![0, 1], vec![1.0, 0.0]);
+        assert!(matches!(result, Err(SparseMatrixError::FaceAfterCoface { row: 0, column: 1, .. })));
+
+        let result = FilteredBoundaryMatrix::new(vec![vec![]], vec![0], vec![f64::NAN]);
+        assert!(matches!(result, Err(SparseMatrixError::InvalidLevel { column: 0, .. })));
+    }
+}
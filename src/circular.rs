@@ -0,0 +1,186 @@
+use log::debug;
+use thiserror::Error;
+
+use super::field::{Field, FieldError, PrimeField};
+use super::homology::{Chain, ChainComplex, PersistenceInterval};
+use super::point_cloud::PointCloudError;
+use super::simplicial_complex::{Simplex, SimplicialComplex};
+
+/// Error types for circular coordinate computation
+#[derive(Error, Debug)]
+pub enum CircularCoordinatesError {
+    #[error("Expected an interval of dimension 1, got dimension {0}")]
+    InvalidDimension(usize),
+    #[error("Interval has no representative cocycle")]
+    MissingCocycle,
+    #[error("Filtration level {level} is outside of the interval [{birth}, {death})")]
+    InvalidLevel { level: f64, birth: f64, death: f64 },
+    #[error("Cocycle does not lift to an integer cocycle, try a different prime")]
+    NotIntegerCocycle,
+    #[error("No interval of dimension 1 found")]
+    NoInterval,
+    #[error(transparent)]
+    Field(#[from] FieldError),
+    #[error(transparent)]
+    PointCloud(#[from] PointCloudError),
+}
+
+/// Solves `L x = b` for the graph Laplacian `L` of a set of edges by the conjugate gradient
+/// method. The right-hand side must sum to zero on each connected component.
+fn solve_laplacian(edges: &[(usize, usize)], b: &[f64]) -> Vec<f64> {
+    let apply = |x: &[f64]| -> Vec<f64> {
+        let mut y = vec![0.0; x.len()];
+        for &(u, v) in edges {
+            let difference = x[u] - x[v];
+            y[u] += difference;
+            y[v] -= difference;
+        }
+        y
+    };
+    let dot = |a: &[f64], b: &[f64]| -> f64 { a.iter().zip(b).map(|(x, y)| x * y).sum() };
+
+    let mut x = vec![0.0; b.len()];
+    let mut residual = b.to_vec();
+    let mut direction = residual.clone();
+    let mut norm = dot(&residual, &residual);
+    let tolerance = 1e-20 * norm.max(f64::MIN_POSITIVE);
+    for _ in 0..10 * b.len() {
+        if norm <= tolerance {
+            break;
+        }
+        let applied = apply(&direction);
+        let step = norm / dot(&direction, &applied);
+        for ix in 0..x.len() {
+            x[ix] += step * direction[ix];
+            residual[ix] -= step * applied[ix];
+        }
+        let next_norm = dot(&residual, &residual);
+        for ix in 0..x.len() {
+            direction[ix] = residual[ix] + next_norm / norm * direction[ix];
+        }
+        norm = next_norm;
+    }
+    x
+}
+
+/// Computes circular coordinates of the vertices of a simplicial complex from a 1-dimensional
+/// persistent cohomology class (de Silva, Morozov and Vejdemo-Johansson).
+///
+/// The representative cocycle over Z/p is lifted to an integer cocycle (with coefficients in
+/// (-p/2, p/2]) on the subcomplex at the given filtration level. It is then smoothed by
+/// subtracting the coboundary of the least squares solution `f` of `δf = α`, found with the
+/// conjugate gradient method on the graph Laplacian, so that the remaining harmonic cocycle
+/// varies as little as possible along edges. The circular coordinate of each vertex is `f`
+/// modulo 1.
+///
+/// # Arguments
+///
+/// * `complex` - Complex the interval was computed from
+/// * `interval` - Interval of dimension 1, computed by cohomology over the given field
+/// * `field` - Field of coefficients used for the computation
+/// * `level` - Filtration level at which to compute the coordinates, within the interval
+///
+/// # Returns
+///
+/// A Result containing either the coordinate in [0, 1) of each vertex (indexed by vertex, with
+/// vertices absent at the given level set to 0) or an error if:
+/// - The interval is not of dimension 1 or has no representative
+/// - The level is outside of the interval
+/// - The cocycle does not lift to an integer cocycle
+pub fn circular_coordinates(
+    complex: &SimplicialComplex,
+    interval: &PersistenceInterval,
+    field: &PrimeField,
+    level: f64,
+) -> Result<Vec<f64>, CircularCoordinatesError> {
+    let Some(&first) = interval.birth_chain.iter().next() else {
+        return Err(CircularCoordinatesError::MissingCocycle);
+    };
+    let dim = complex.chain(first).dim();
+    if dim != 1 {
+        return Err(CircularCoordinatesError::InvalidDimension(dim));
+    }
+    if !(interval.birth <= level && level < interval.death) {
+        return Err(CircularCoordinatesError::InvalidLevel { level, birth: interval.birth, death: interval.death });
+    }
+
+    // Lift coefficients to the symmetric range of integers
+    let p = field.characteristic() as i64;
+    let lift = |ix: usize| -> f64 {
+        let value = interval.coefficients.get(&ix).copied().unwrap_or(0) as i64;
+        (if value > p / 2 { value - p } else { value }) as f64
+    };
+
+    // Truncation keeps the indices of the remaining simplices
+    let subcomplex = complex.truncate(level);
+    for (ix, simplex) in subcomplex.simplices.iter().enumerate() {
+        if simplex.dim() == 2 {
+            // The cocycle must vanish on the (signed) boundary of every triangle
+            let value: f64 =
+                subcomplex.signed_boundary(ix).into_iter().map(|(face, sign)| sign as f64 * lift(face)).sum();
+            if value != 0.0 {
+                return Err(CircularCoordinatesError::NotIntegerCocycle);
+            }
+        }
+    }
+
+    // Vertices of an edge [u, v] (u < v) have coboundary with sign -1 on u and 1 on v
+    let n_vertices = subcomplex.simplices.iter().flat_map(|s| s.vertices.iter()).max().map_or(0, |&v| v + 1);
+    let mut edges = Vec::new();
+    let mut rhs = vec![0.0; n_vertices];
+    for (ix, simplex) in subcomplex.simplices.iter().enumerate().filter(|(_, s)| s.dim() == 1) {
+        let (u, v) = (simplex.vertices[0], simplex.vertices[1]);
+        let value = lift(ix);
+        rhs[u] -= value;
+        rhs[v] += value;
+        edges.push((u, v));
+    }
+    debug!("Smoothing cocycle over {} vertices and {} edges", n_vertices, edges.len());
+
+    let f = solve_laplacian(&edges, &rhs);
+    Ok((0..n_vertices)
+        .map(|v| if subcomplex.index_of(&Simplex::new(vec![v])).is_some() { f[v].rem_euclid(1.0) } else { 0.0 })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::homology::{PersistenceAlgorithm, PersistenceOptions};
+    use crate::test_utils::circle_cloud;
+
+    #[test]
+    fn test_circle_coordinates() {
+        let n = 20;
+        let coordinates = circle_cloud(n, 0.0).circular_coordinates(1.0, 47).unwrap();
+        assert_eq!(coordinates.len(), n);
+
+        // Consecutive points are a constant step apart, in one direction or the other
+        let step = (coordinates[1] - coordinates[0]).rem_euclid(1.0);
+        assert!((step - 1.0 / n as f64).abs() < 1e-6 || (step - 1.0 + 1.0 / n as f64).abs() < 1e-6);
+        for ix in 0..n {
+            let next = (coordinates[(ix + 1) % n] - coordinates[ix]).rem_euclid(1.0);
+            assert!((next - step).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let complex = circle_cloud(8, 0.0).vietoris_rips_complex(2, 2.0).unwrap();
+        let field = PrimeField::new(3).unwrap();
+        let options = PersistenceOptions { algorithm: PersistenceAlgorithm::Cohomology, field, ..Default::default() };
+        let intervals = complex.persistence_intervals_with_options(&options);
+
+        let loop_interval =
+            intervals[&1].iter().max_by(|a, b| (a.death - a.birth).total_cmp(&(b.death - b.birth))).unwrap();
+        assert!(circular_coordinates(&complex, loop_interval, &field, loop_interval.birth).is_ok());
+        assert!(matches!(
+            circular_coordinates(&complex, loop_interval, &field, loop_interval.death),
+            Err(CircularCoordinatesError::InvalidLevel { .. })
+        ));
+        assert!(matches!(
+            circular_coordinates(&complex, &intervals[&0][0], &field, 0.0),
+            Err(CircularCoordinatesError::InvalidDimension(0))
+        ));
+    }
+}
@@ -5,8 +5,14 @@ use thiserror::Error;
 pub enum CombinatoricsError {
     #[error("Size {size} exceeds input length {len}")]
     InvalidSize { size: usize, len: usize },
+    #[error("Rank {rank} is out of range for subsets of size {size}")]
+    InvalidRank { rank: u64, size: usize },
     #[error("Input too large: would generate {0} combinations")]
     TooLarge(usize),
+    #[error("Subsets of size {size} of {n_elements} elements cannot be ranked in 64 bits")]
+    Overflow { n_elements: usize, size: usize },
+    #[error("Invalid subset {0:?}: elements must be strictly increasing and in range")]
+    InvalidSubset(Vec<usize>),
 }
 
 /// Iterator over combinations of elements.
@@ -188,6 +194,167 @@ pub fn generate_subsets<T: Clone>(elements: &[T], max_size: usize) -> Vec<Vec<T>
     subsets
 }
 
+/// Ranks and unranks subsets of `{0, ..., n - 1}` of bounded size with the combinatorial number
+/// system, so that a simplex can be stored as a single `u64` key.
+///
+/// The k-subset `v_0 < v_1 < ... < v_{k-1}` has rank `C(v_0, 1) + C(v_1, 2) + ... + C(v_{k-1}, k)`.
+/// Ranks of subsets of the same size are contiguous from 0 to `C(n, k) - 1`, and follow the
+/// colexicographic order of the subsets. Since the rank does not depend on `n`, keys remain
+/// valid when vertices are added, as long as they stay below the capacity of the table.
+#[derive(Debug, Clone)]
+pub struct BinomialTable {
+    /// `table[k][m]` is the binomial coefficient C(m, k)
+    table: Vec<Vec<u64>>,
+}
+
+impl BinomialTable {
+    /// Creates a table for ranking subsets of up to `max_size` elements among `n_elements`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_elements` - Number of elements (e.g. the number of vertices of a complex)
+    /// * `max_size` - Maximum size of the subsets (e.g. the maximum dimension plus one)
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the table or an error if some rank would not fit in a u64.
+    pub fn new(n_elements: usize, max_size: usize) -> Result<Self, CombinatoricsError> {
+        let mut table: Vec<Vec<u64>> = vec![vec![1; n_elements + 1]];
+        for k in 1..=max_size {
+            let mut row = vec![0; n_elements + 1];
+            for m in 1..=n_elements {
+                // Pascal's rule: C(m, k) = C(m - 1, k - 1) + C(m - 1, k)
+                row[m] = table[k - 1][m - 1]
+                    .checked_add(row[m - 1])
+                    .ok_or(CombinatoricsError::Overflow { n_elements, size: max_size })?;
+            }
+            table.push(row);
+        }
+        Ok(Self { table })
+    }
+
+    /// Returns the number of elements covered by the table.
+    pub fn n_elements(&self) -> usize {
+        self.table[0].len() - 1
+    }
+
+    /// Returns the maximum size of the subsets covered by the table.
+    pub fn max_size(&self) -> usize {
+        self.table.len() - 1
+    }
+
+    /// Returns the binomial coefficient C(m, k), for m up to the number of elements and k up to
+    /// the maximum size.
+    pub fn binomial(&self, m: usize, k: usize) -> u64 {
+        self.table[k][m]
+    }
+
+    /// Returns the rank of a subset.
+    ///
+    /// # Arguments
+    ///
+    /// * `subset` - Strictly increasing elements, below the number of elements of the table
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the rank or an error if the subset is not strictly increasing,
+    /// is out of range, or is larger than the maximum size.
+    pub fn rank(&self, subset: &[usize]) -> Result<u64, CombinatoricsError> {
+        let valid = subset.len() <= self.max_size()
+            && subset.windows(2).all(|w| w[0] < w[1])
+            && subset.last().is_none_or(|&v| v < self.n_elements());
+        if !valid {
+            return Err(CombinatoricsError::InvalidSubset(subset.to_vec()));
+        }
+        Ok(subset.iter().enumerate().map(|(i, &v)| self.binomial(v, i + 1)).sum())
+    }
+
+    /// Returns the subset of the given size with the given rank.
+    ///
+    /// Each element is the largest `v` with `C(v, i) <= rank`, found by binary search, before
+    /// removing its contribution from the rank.
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - Rank of the subset, below `C(n_elements, size)`
+    /// * `size` - Number of elements of the subset, at most the maximum size
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the strictly increasing elements or an error if the rank or
+    /// size is out of range.
+    pub fn unrank(&self, mut rank: u64, size: usize) -> Result<Vec<usize>, CombinatoricsError> {
+        if size > self.max_size() || size > self.n_elements() {
+            return Err(CombinatoricsError::InvalidSize { size, len: self.max_size().min(self.n_elements()) });
+        }
+        if rank >= self.binomial(self.n_elements(), size) {
+            return Err(CombinatoricsError::InvalidRank { rank, size });
+        }
+        let mut subset = vec![0; size];
+        let mut upper = self.n_elements();
+        for i in (1..=size).rev() {
+            let row = &self.table[i][..upper];
+            let v = row.partition_point(|&c| c <= rank) - 1;
+            subset[i - 1] = v;
+            rank -= row[v];
+            upper = v;
+        }
+        Ok(subset)
+    }
+
+    /// Returns the ranks of the faces of codimension 1 of a subset, obtained by removing each
+    /// element in turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - Rank of the subset
+    /// * `size` - Number of elements of the subset
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the ranks of the faces (in the order of the removed elements)
+    /// or an error if the rank or size is out of range.
+    pub fn facets(&self, rank: u64, size: usize) -> Result<Vec<u64>, CombinatoricsError> {
+        let subset = self.unrank(rank, size)?;
+        Ok((0..size)
+            .map(|i| {
+                // Elements after the removed one move down one position
+                subset[..i].iter().enumerate().map(|(j, &v)| self.binomial(v, j + 1)).sum::<u64>()
+                    + subset[i + 1..].iter().enumerate().map(|(j, &v)| self.binomial(v, i + j + 1)).sum::<u64>()
+            })
+            .collect())
+    }
+
+    /// Returns the ranks of the cofaces of codimension 1 of a subset, obtained by adding each
+    /// element not in the subset.
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - Rank of the subset
+    /// * `size` - Number of elements of the subset, below the maximum size
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the ranks of the cofaces (by increasing added element) or an
+    /// error if the rank or size is out of range.
+    pub fn cofacets(&self, rank: u64, size: usize) -> Result<Vec<u64>, CombinatoricsError> {
+        if size >= self.max_size() {
+            return Err(CombinatoricsError::InvalidSize { size: size + 1, len: self.max_size() });
+        }
+        let subset = self.unrank(rank, size)?;
+        let mut cofacets = Vec::with_capacity(self.n_elements() - size);
+        for v in (0..self.n_elements()).filter(|v| subset.binary_search(v).is_err()) {
+            let position = subset.partition_point(|&u| u < v);
+            // Elements after the new one move up one position
+            let coface = subset[..position].iter().enumerate().map(|(i, &u)| self.binomial(u, i + 1)).sum::<u64>()
+                + self.binomial(v, position + 1)
+                + subset[position..].iter().enumerate().map(|(i, &u)| self.binomial(u, position + i + 2)).sum::<u64>();
+            cofacets.push(coface);
+        }
+        Ok(cofacets)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,6 +421,68 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rank_and_unrank() {
+        let table = BinomialTable::new(6, 3).unwrap();
+        assert_eq!(table.binomial(6, 3), 20);
+
+        // Ranks of 3-subsets follow the colexicographic order, from 0 to C(6, 3) - 1
+        let subsets = generate_combinations(&(0..6).collect::<Vec<usize>>(), 3).unwrap();
+        let mut ranks: Vec<u64> = subsets.iter().map(|s| table.rank(s).unwrap()).collect();
+        for (subset, &rank) in subsets.iter().zip(&ranks) {
+            assert_eq!(&table.unrank(rank, 3).unwrap(), subset);
+        }
+        ranks.sort();
+        assert_eq!(ranks, (0..20).collect::<Vec<u64>>());
+        assert_eq!(table.rank(&[0, 1, 2]).unwrap(), 0);
+        assert_eq!(table.rank(&[0, 1, 3]).unwrap(), 1);
+        assert_eq!(table.rank(&[3, 4, 5]).unwrap(), 19);
+        assert_eq!(table.rank(&[]).unwrap(), 0);
+
+        assert!(table.rank(&[1, 1]).is_err());
+        assert!(table.rank(&[0, 6]).is_err());
+        assert!(table.rank(&[0, 1, 2, 3]).is_err());
+        assert!(matches!(table.unrank(20, 3), Err(CombinatoricsError::InvalidRank { rank: 20, size: 3 })));
+        assert!(matches!(table.unrank(0, 4), Err(CombinatoricsError::InvalidSize { size: 4, len: 3 })));
+        assert!(matches!(table.cofacets(20, 2), Err(CombinatoricsError::InvalidRank { rank: 20, size: 2 })));
+    }
+
+    #[test]
+    fn test_facets_and_cofacets() {
+        let table = BinomialTable::new(7, 4).unwrap();
+        for size in 1..=3 {
+            for rank in 0..table.binomial(7, size) {
+                let subset = table.unrank(rank, size).unwrap();
+                let expected: Vec<u64> = (0..size)
+                    .map(|i| {
+                        let mut face = subset.clone();
+                        face.remove(i);
+                        table.rank(&face).unwrap()
+                    })
+                    .collect();
+                assert_eq!(table.facets(rank, size).unwrap(), expected);
+
+                let expected: Vec<u64> = (0..7)
+                    .filter(|v| !subset.contains(v))
+                    .map(|v| {
+                        let mut coface = subset.clone();
+                        coface.push(v);
+                        coface.sort();
+                        table.rank(&coface).unwrap()
+                    })
+                    .collect();
+                assert_eq!(table.cofacets(rank, size).unwrap(), expected);
+            }
+        }
+        assert!(table.cofacets(0, 4).is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert!(BinomialTable::new(1_000_000, 3).is_ok());
+        assert!(matches!(BinomialTable::new(1_000_000, 5), Err(CombinatoricsError::Overflow { .. })));
+    }
+
     #[test]
     fn test_large_input() {
         let v: Vec<_> = (0..100).collect();
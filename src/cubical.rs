@@ -0,0 +1,327 @@
+use log::debug;
+use ndarray::{Array, ArrayD, Dimension, IxDyn};
+use ordered_float::OrderedFloat;
+use std::collections::{BTreeMap, HashSet};
+use thiserror::Error;
+
+use super::homology::{Chain, ChainComplex};
+
+/// Error types for cubical complex operations
+#[derive(Error, Debug)]
+pub enum CubicalComplexError {
+    #[error("Grid must be non-empty with at least one axis")]
+    EmptyGrid,
+    #[error("Expected periodicity for {expected} axes, got {actual}")]
+    PeriodicMismatch { expected: usize, actual: usize },
+    #[error("Invalid value {value} at {index:?}")]
+    InvalidValue { value: f64, index: Vec<usize> },
+}
+
+/// How the values of a grid are turned into a filtration of cubes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Construction {
+    /// V-construction: each value is a vertex, and every cube appears with the largest value of
+    /// its vertices
+    V,
+    /// T-construction: each value is a top-dimensional cube (pixel or voxel), and every cube
+    /// appears with the smallest value of the top-dimensional cubes containing it
+    #[default]
+    T,
+}
+
+/// An elementary cube, given by its coordinates in the cell grid.
+///
+/// Each coordinate is even if the cube is a single point along that axis, and odd if it spans
+/// an interval, so that the dimension of the cube is its number of odd coordinates.
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+pub struct Cube {
+    /// Coordinates of the cube in the cell grid
+    pub coordinates: Vec<usize>,
+}
+
+impl Chain for Cube {
+    fn dim(&self) -> usize {
+        self.coordinates.iter().filter(|&&c| c % 2 == 1).count()
+    }
+}
+
+/// A cubical complex with a filtration built from the values of a grid (e.g. an image or a
+/// voxel grid), avoiding the blow-up in size of a triangulation.
+#[derive(Debug, Clone)]
+pub struct CubicalComplex {
+    /// Cubes in the complex, ordered by filtration level
+    pub cubes: Vec<Cube>,
+    /// Filtration levels corresponding to each cube
+    pub levels: Vec<f64>,
+    /// Size of the cell grid along each axis
+    shape: Vec<usize>,
+    /// Whether each axis wraps around
+    periodic: Vec<bool>,
+    /// Index in the complex of each cube of the cell grid, in row-major order
+    positions: Vec<usize>,
+}
+
+impl CubicalComplex {
+    /// Creates a filtered cubical complex from a grid of values.
+    ///
+    /// Cubes are ordered by filtration level, then dimension, then position in the grid, so
+    /// that every cube comes after its faces.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - Grid of values (typically 2D for images and 3D for voxel grids)
+    /// * `construction` - Whether values are assigned to vertices or top-dimensional cubes
+    /// * `periodic` - Whether each axis wraps around (empty for no periodic axes)
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the new CubicalComplex or an error if:
+    /// - The grid is empty
+    /// - The periodicity does not match the number of axes
+    /// - Any value is NaN
+    pub fn new<D: Dimension>(
+        values: &Array<f64, D>,
+        construction: Construction,
+        periodic: &[bool],
+    ) -> Result<Self, CubicalComplexError> {
+        let values: ArrayD<f64> = values.view().into_dyn().as_standard_layout().into_owned();
+        let grid = values.shape().to_vec();
+        if grid.is_empty() || values.is_empty() {
+            return Err(CubicalComplexError::EmptyGrid);
+        }
+        let periodic = if periodic.is_empty() { vec![false; grid.len()] } else { periodic.to_vec() };
+        if periodic.len() != grid.len() {
+            return Err(CubicalComplexError::PeriodicMismatch { expected: grid.len(), actual: periodic.len() });
+        }
+        if let Some((index, &value)) = values.indexed_iter().find(|(_, v)| v.is_nan()) {
+            return Err(CubicalComplexError::InvalidValue { value, index: index.slice().to_vec() });
+        }
+
+        let shape: Vec<usize> = grid
+            .iter()
+            .zip(&periodic)
+            .map(|(&n, &wraps)| match (construction, wraps) {
+                (_, true) => 2 * n,
+                (Construction::V, false) => 2 * n - 1,
+                (Construction::T, false) => 2 * n + 1,
+            })
+            .collect();
+        let n_cubes: usize = shape.iter().product();
+        debug!("Building cubical complex with {} cubes from grid {:?}", n_cubes, grid);
+
+        // Grid values adjacent to a cell grid coordinate along one axis
+        let adjacent = |axis: usize, c: usize| -> Vec<usize> {
+            let n = grid[axis];
+            match (construction, c % 2) {
+                (Construction::V, 0) | (Construction::T, 1) => vec![c / 2],
+                (Construction::V, _) => vec![c / 2, (c / 2 + 1) % n],
+                (Construction::T, _) if periodic[axis] => vec![(c / 2 + n - 1) % n, c / 2],
+                (Construction::T, _) => {
+                    (c / 2).checked_sub(1).into_iter().chain((c / 2 < n).then_some(c / 2)).collect()
+                },
+            }
+        };
+
+        let mut cubes = Vec::with_capacity(n_cubes);
+        let mut levels = Vec::with_capacity(n_cubes);
+        for flat in 0..n_cubes {
+            let coordinates = unflatten(flat, &shape);
+            let choices: Vec<Vec<usize>> = coordinates.iter().enumerate().map(|(axis, &c)| adjacent(axis, c)).collect();
+
+            // Combine the values of the product of the adjacent grid indices
+            let mut level = match construction {
+                Construction::V => f64::NEG_INFINITY,
+                Construction::T => f64::INFINITY,
+            };
+            let mut index = vec![0; choices.len()];
+            loop {
+                let point: Vec<usize> = index.iter().zip(&choices).map(|(&i, choice)| choice[i]).collect();
+                let value = values[IxDyn(&point)];
+                level = match construction {
+                    Construction::V => level.max(value),
+                    Construction::T => level.min(value),
+                };
+                let Some(axis) = (0..choices.len()).rev().find(|&axis| index[axis] + 1 < choices[axis].len()) else {
+                    break;
+                };
+                index[axis] += 1;
+                index[axis + 1..].fill(0);
+            }
+            cubes.push(Cube { coordinates });
+            levels.push(level);
+        }
+
+        // Faces have lower dimension and no higher level, so they come first
+        let mut order: Vec<usize> = (0..n_cubes).collect();
+        order.sort_by_key(|&flat| (OrderedFloat(levels[flat]), cubes[flat].dim(), flat));
+        let mut positions = vec![0; n_cubes];
+        for (ix, &flat) in order.iter().enumerate() {
+            positions[flat] = ix;
+        }
+        let sorted_cubes = order.iter().map(|&flat| cubes[flat].clone()).collect();
+        let sorted_levels = order.iter().map(|&flat| levels[flat]).collect();
+
+        Ok(Self { cubes: sorted_cubes, levels: sorted_levels, shape, periodic, positions })
+    }
+
+    /// Returns the index of a cube in the complex, if it exists.
+    pub fn index_of(&self, cube: &Cube) -> Option<usize> {
+        if cube.coordinates.len() != self.shape.len() || cube.coordinates.iter().zip(&self.shape).any(|(&c, &n)| c >= n)
+        {
+            return None;
+        }
+        Some(self.positions[flatten(&cube.coordinates, &self.shape)])
+    }
+}
+
+/// Converts a row-major flat index to coordinates in a grid of the given shape.
+fn unflatten(mut flat: usize, shape: &[usize]) -> Vec<usize> {
+    let mut coordinates = vec![0; shape.len()];
+    for (axis, &n) in shape.iter().enumerate().rev() {
+        coordinates[axis] = flat % n;
+        flat /= n;
+    }
+    coordinates
+}
+
+/// Converts coordinates in a grid of the given shape to a row-major flat index.
+fn flatten(coordinates: &[usize], shape: &[usize]) -> usize {
+    coordinates.iter().zip(shape).fold(0, |flat, (&c, &n)| flat * n + c)
+}
+
+impl ChainComplex<Cube> for CubicalComplex {
+    fn chain(&self, index: usize) -> &Cube {
+        &self.cubes[index]
+    }
+
+    fn chains(&self) -> &Vec<Cube> {
+        &self.cubes
+    }
+
+    fn boundary(&self, index: usize) -> HashSet<usize> {
+        self.signed_boundary(index).into_iter().filter(|&(_, c)| c % 2 != 0).map(|(ix, _)| ix).collect()
+    }
+
+    /// The boundary of a cube is the alternating sum over its spanning axes of the upper face
+    /// minus the lower face. Faces wrapping around a periodic axis of size one coincide and
+    /// cancel out.
+    fn signed_boundary(&self, index: usize) -> Vec<(usize, i64)> {
+        let coordinates = &self.cubes[index].coordinates;
+        let mut bounds: BTreeMap<usize, i64> = BTreeMap::new();
+        let mut sign = 1;
+        for (axis, &c) in coordinates.iter().enumerate().filter(|(_, &c)| c % 2 == 1) {
+            let mut face = coordinates.clone();
+            face[axis] = c - 1;
+            *bounds.entry(self.positions[flatten(&face, &self.shape)]).or_default() -= sign;
+            face[axis] = if self.periodic[axis] { (c + 1) % self.shape[axis] } else { c + 1 };
+            *bounds.entry(self.positions[flatten(&face, &self.shape)]).or_default() += sign;
+            sign = -sign;
+        }
+        bounds.into_iter().filter(|&(_, c)| c != 0).collect()
+    }
+
+    fn filtration_level(&self, index: usize) -> OrderedFloat<f64> {
+        OrderedFloat(self.levels[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::PrimeField;
+    use crate::homology::{PersistenceInterval, PersistenceOptions};
+    use ndarray::{array, Array3};
+    use std::collections::HashMap;
+
+    fn pairs(intervals: &HashMap<usize, Vec<PersistenceInterval>>, dim: usize) -> Vec<(f64, f64)> {
+        let mut pairs: Vec<(f64, f64)> =
+            intervals[&dim].iter().filter(|i| i.death > i.birth).map(|i| (i.birth, i.death)).collect();
+        pairs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        pairs
+    }
+
+    #[test]
+    fn test_constructions() {
+        let image = array![[0.0, 0.0, 0.0], [0.0, 5.0, 0.0], [0.0, 0.0, 0.0]];
+
+        let complex = CubicalComplex::new(&image, Construction::T, &[]).unwrap();
+        assert_eq!(complex.len(), 49);
+        assert_eq!(complex.filtration_level(complex.index_of(&Cube { coordinates: vec![3, 3] }).unwrap()), 5.0);
+        assert_eq!(complex.filtration_level(complex.index_of(&Cube { coordinates: vec![2, 2] }).unwrap()), 0.0);
+        let intervals = complex.persistence_intervals();
+        assert_eq!(pairs(&intervals, 0), vec![(0.0, f64::INFINITY)]);
+        assert_eq!(pairs(&intervals, 1), vec![(0.0, 5.0)]);
+
+        let complex = CubicalComplex::new(&image, Construction::V, &[]).unwrap();
+        assert_eq!(complex.len(), 25);
+        assert_eq!(complex.filtration_level(complex.index_of(&Cube { coordinates: vec![1, 2] }).unwrap()), 5.0);
+        let intervals = complex.persistence_intervals();
+        assert_eq!(pairs(&intervals, 0), vec![(0.0, f64::INFINITY)]);
+        assert_eq!(pairs(&intervals, 1), vec![(0.0, 5.0)]);
+        assert!(complex.index_of(&Cube { coordinates: vec![5, 0] }).is_none());
+    }
+
+    #[test]
+    fn test_voxels() {
+        // A shell of voxels enclosing a void
+        let mut voxels = Array3::zeros((3, 3, 3));
+        voxels[[1, 1, 1]] = 2.0;
+        let complex = CubicalComplex::new(&voxels, Construction::T, &[]).unwrap();
+        let intervals = complex.persistence_intervals();
+        assert_eq!(pairs(&intervals, 0), vec![(0.0, f64::INFINITY)]);
+        assert!(pairs(&intervals, 1).is_empty());
+        assert_eq!(pairs(&intervals, 2), vec![(0.0, 2.0)]);
+    }
+
+    #[test]
+    fn test_periodic() {
+        // A periodic grid is a torus, for both constructions and any grid size
+        for construction in [Construction::V, Construction::T] {
+            for n in [1, 3] {
+                let complex = CubicalComplex::new(&Array::zeros((n, n)), construction, &[true, true]).unwrap();
+                let homology = complex.integral_homology();
+                assert_eq!((0..3).map(|dim| homology[&dim].rank).collect::<Vec<_>>(), vec![1, 2, 1]);
+
+                let options = PersistenceOptions { field: PrimeField::new(3).unwrap(), ..Default::default() };
+                let intervals = complex.persistence_intervals_with_options(&options);
+                assert_eq!(pairs(&intervals, 1), vec![(0.0, f64::INFINITY); 2]);
+            }
+        }
+
+        // Wrapping one axis of a strip gives a cylinder
+        let complex = CubicalComplex::new(&Array::zeros((4, 2)), Construction::T, &[true, false]).unwrap();
+        let homology = complex.integral_homology();
+        assert_eq!((0..3).map(|dim| homology[&dim].rank).collect::<Vec<_>>(), vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn test_boundary_of_boundary() {
+        let voxels = Array3::from_shape_fn((2, 3, 2), |(i, j, k)| (i + 2 * j + 3 * k) as f64);
+        let complex = CubicalComplex::new(&voxels, Construction::V, &[false, true, false]).unwrap();
+        for ix in 0..complex.len() {
+            let mut total: HashMap<usize, i64> = HashMap::new();
+            for (face, sign) in complex.signed_boundary(ix) {
+                assert!(face < ix);
+                assert_eq!(complex.chain(face).dim() + 1, complex.chain(ix).dim());
+                for (subface, subsign) in complex.signed_boundary(face) {
+                    *total.entry(subface).or_default() += sign * subsign;
+                }
+            }
+            assert!(total.values().all(|&c| c == 0));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let empty: Array<f64, _> = Array::zeros((0, 3));
+        assert!(matches!(CubicalComplex::new(&empty, Construction::T, &[]), Err(CubicalComplexError::EmptyGrid)));
+        assert!(matches!(
+            CubicalComplex::new(&array![[0.0]], Construction::T, &[true]),
+            Err(CubicalComplexError::PeriodicMismatch { expected: 2, actual: 1 })
+        ));
+        assert!(matches!(
+            CubicalComplex::new(&array![[0.0, f64::NAN]], Construction::V, &[]),
+            Err(CubicalComplexError::InvalidValue { .. })
+        ));
+    }
+}
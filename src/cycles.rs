@@ -0,0 +1,358 @@
+use log::debug;
+use ordered_float::OrderedFloat;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use super::field::Z2;
+use super::homology::{Chain, ChainComplex, PersistenceInterval};
+use super::reduction::SparseColumn;
+
+/// Largest dimension of the space of volumes searched exhaustively by [`volume_optimal_cycle`].
+const MAX_EXHAUSTIVE_NULLITY: usize = 16;
+
+/// Incremental Gaussian elimination of Z/2 columns by pivot, remembering which input columns
+/// were combined into each reduced column.
+#[derive(Default)]
+struct Eliminator {
+    /// Reduced column and its combination of input columns, by pivot
+    pivots: HashMap<usize, (SparseColumn, SparseColumn)>,
+}
+
+impl Eliminator {
+    /// Reduces a column against the stored pivots, until it is empty or its pivot is new.
+    fn reduce(&self, mut column: SparseColumn, mut combination: SparseColumn) -> (SparseColumn, SparseColumn) {
+        while let Some((reduced, used)) = column.pivot().and_then(|pivot| self.pivots.get(&pivot)) {
+            column.add_scaled(reduced, 1, &Z2);
+            combination.add_scaled(used, 1, &Z2);
+        }
+        (column, combination)
+    }
+
+    /// Adds a column to the span.
+    ///
+    /// # Returns
+    ///
+    /// The combination of input columns summing to zero, if the column was already in the span.
+    fn insert(&mut self, column: SparseColumn, combination: SparseColumn) -> Option<SparseColumn> {
+        let (column, combination) = self.reduce(column, combination);
+        match column.pivot() {
+            Some(pivot) => {
+                self.pivots.insert(pivot, (column, combination));
+                None
+            },
+            None => Some(combination),
+        }
+    }
+}
+
+/// Returns the Z/2 sum of the boundaries of the given chains.
+fn boundary_of<T: Chain + std::fmt::Debug, C: ChainComplex<T> + ?Sized>(
+    complex: &C,
+    chains: impl Iterator<Item = usize>,
+) -> SparseColumn {
+    chains.fold(SparseColumn::default(), |mut sum, ix| {
+        sum.add_scaled(&SparseColumn::from(complex.boundary(ix)), 1, &Z2);
+        sum
+    })
+}
+
+/// Returns the two endpoints of an edge over Z/2, or None if its boundary is not two vertices.
+fn endpoints<T: Chain + std::fmt::Debug, C: ChainComplex<T> + ?Sized>(
+    complex: &C,
+    edge: usize,
+) -> Option<(usize, usize)> {
+    let boundary: Vec<usize> = complex.boundary(edge).into_iter().collect();
+    match boundary[..] {
+        [start, end] => Some((start, end)),
+        _ => None,
+    }
+}
+
+/// Computes a shortest representative cycle of a 1-dimensional persistence interval.
+///
+/// Edges are weighted by their filtration level, i.e. by their length in a Vietoris-Rips
+/// filtration. The candidate is the birth edge closed up by a shortest path between its
+/// endpoints through older edges, found with Dijkstra's algorithm. It is only accepted if it is
+/// homologous to the interval's representative in the complex at birth, so that it represents
+/// the same feature over the whole interval, and the death chain is adjusted accordingly.
+///
+/// # Arguments
+///
+/// * `complex` - Complex the interval was computed from, over Z/2 and by homology
+/// * `interval` - Interval of dimension 1, with representatives
+///
+/// # Returns
+///
+/// The (birth chain, death chain) of the shortest representative, or None if the interval has
+/// no representative or the shortest cycle does not represent the same feature.
+pub fn shortest_cycle<T: Chain + std::fmt::Debug, C: ChainComplex<T> + ?Sized>(
+    complex: &C,
+    interval: &PersistenceInterval,
+) -> Option<(HashSet<usize>, Option<HashSet<usize>>)> {
+    let birth = interval.birth_chain.iter().copied().max()?;
+    if complex.chain(birth).dim() != 1 {
+        return None;
+    }
+    // Edges of CW complexes may be loops, or attached with an even coefficient, and then have
+    // no endpoints over Z/2
+    let (source, target) = endpoints(complex, birth)?;
+
+    // Graph of the edges older than the birth edge
+    let mut neighbours: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+    for ix in (0..birth).filter(|&ix| complex.chain(ix).dim() == 1) {
+        if let Some((start, end)) = endpoints(complex, ix) {
+            neighbours.entry(start).or_default().push((end, ix));
+            neighbours.entry(end).or_default().push((start, ix));
+        }
+    }
+
+    let mut distances: HashMap<usize, f64> = HashMap::from([(source, 0.0)]);
+    let mut previous: HashMap<usize, (usize, usize)> = HashMap::new();
+    let mut heap = BinaryHeap::from([Reverse((OrderedFloat(0.0), source))]);
+    while let Some(Reverse((OrderedFloat(distance), vertex))) = heap.pop() {
+        if vertex == target {
+            break;
+        }
+        if distance > distances[&vertex] {
+            continue;
+        }
+        for &(next, edge) in neighbours.get(&vertex).into_iter().flatten() {
+            let candidate = distance + complex.filtration_level(edge).into_inner();
+            if distances.get(&next).is_none_or(|&d| candidate < d) {
+                distances.insert(next, candidate);
+                previous.insert(next, (vertex, edge));
+                heap.push(Reverse((OrderedFloat(candidate), next)));
+            }
+        }
+    }
+
+    let mut cycle = HashSet::from([birth]);
+    let mut vertex = target;
+    while vertex != source {
+        let (prev, edge) = previous.get(&vertex).copied()?;
+        cycle.insert(edge);
+        vertex = prev;
+    }
+    if cycle == interval.birth_chain {
+        return Some((cycle, interval.death_chain.clone()));
+    }
+
+    // The difference must bound triangles present at birth
+    let mut eliminator = Eliminator::default();
+    for ix in (0..birth).filter(|&ix| complex.chain(ix).dim() == 2) {
+        eliminator.insert(SparseColumn::from(complex.boundary(ix)), SparseColumn::unit(ix));
+    }
+    let difference: HashSet<usize> = cycle.symmetric_difference(&interval.birth_chain).copied().collect();
+    let (remainder, filling) = eliminator.reduce(SparseColumn::from(difference), SparseColumn::default());
+    if !remainder.is_empty() {
+        debug!("Shortest cycle through {} is not homologous to the representative", birth);
+        return None;
+    }
+
+    let death_chain = interval.death_chain.as_ref().map(|chain| {
+        let mut chain = SparseColumn::from(chain.clone());
+        chain.add_scaled(&filling, 1, &Z2);
+        HashSet::from(&chain)
+    });
+    Some((cycle, death_chain))
+}
+
+/// Computes a volume-optimal representative cycle of a finite persistence interval.
+///
+/// The volume is a chain of one dimension higher, made of the death chain and chains born
+/// strictly between birth and death, whose boundary only contains chains present at birth. Its
+/// boundary is then a representative cycle of the interval, and the volume kills it at death.
+/// Volumes form an affine space over Z/2, in which the smallest volume is found exhaustively if
+/// its dimension is at most 16, and otherwise by a greedy local search from the death chain
+/// (which may not reach the optimum).
+///
+/// # Arguments
+///
+/// * `complex` - Complex the interval was computed from, over Z/2 and by homology
+/// * `interval` - Finite interval with representatives
+///
+/// # Returns
+///
+/// The (cycle, volume) of the smallest volume found, or None if the interval is infinite or has
+/// no representatives.
+pub fn volume_optimal_cycle<T: Chain + std::fmt::Debug, C: ChainComplex<T> + ?Sized>(
+    complex: &C,
+    interval: &PersistenceInterval,
+) -> Option<(HashSet<usize>, HashSet<usize>)> {
+    let birth = interval.birth_chain.iter().copied().max()?;
+    let death_chain = interval.death_chain.as_ref()?;
+    let death = death_chain.iter().copied().max()?;
+    let dim = complex.chain(death).dim();
+
+    // Parts of volumes born before the birth have their boundary present at birth, and are
+    // best left out
+    let initial = SparseColumn::from(death_chain.iter().copied().filter(|&ix| ix > birth).collect::<HashSet<usize>>());
+
+    // Directions in which the volume can move without its boundary leaving the complex at birth
+    let mut eliminator = Eliminator::default();
+    let mut directions = Vec::new();
+    for ix in (birth + 1..death).filter(|&ix| complex.chain(ix).dim() == dim) {
+        let boundary: HashSet<usize> = complex.boundary(ix).into_iter().filter(|&face| face > birth).collect();
+        if let Some(direction) = eliminator.insert(SparseColumn::from(boundary), SparseColumn::unit(ix)) {
+            directions.push(direction);
+        }
+    }
+    debug!("Searching {} directions for a volume-optimal cycle born at {}", directions.len(), birth);
+
+    let size = |column: &SparseColumn| column.indices().count();
+    let mut best = initial.clone();
+    if directions.len() <= MAX_EXHAUSTIVE_NULLITY {
+        // Visit every volume, flipping one direction at a time in Gray code order
+        let mut volume = initial;
+        for step in 1..1usize << directions.len() {
+            volume.add_scaled(&directions[step.trailing_zeros() as usize], 1, &Z2);
+            if size(&volume) < size(&best) {
+                best = volume.clone();
+            }
+        }
+    } else {
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for direction in &directions {
+                let mut candidate = best.clone();
+                candidate.add_scaled(direction, 1, &Z2);
+                if size(&candidate) < size(&best) {
+                    best = candidate;
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    let cycle = boundary_of(complex, best.indices());
+    Some((HashSet::from(&cycle), HashSet::from(&best)))
+}
+
+/// Replaces the representatives of persistence intervals computed over Z/2 by homology with
+/// shortest cycles in dimension 1 and volume-optimal cycles in higher dimensions, where found.
+pub(crate) fn minimize_cycles<T: Chain + std::fmt::Debug, C: ChainComplex<T> + ?Sized>(
+    complex: &C,
+    intervals: &mut HashMap<usize, Vec<PersistenceInterval>>,
+) {
+    for (&dim, ints) in intervals.iter_mut() {
+        for interval in ints.iter_mut().filter(|i| !i.birth_chain.is_empty()) {
+            let optimal = match dim {
+                0 => None,
+                1 => shortest_cycle(complex, interval),
+                _ => volume_optimal_cycle(complex, interval).map(|(cycle, volume)| (cycle, Some(volume))),
+            };
+            if let Some((birth_chain, death_chain)) = optimal {
+                interval.coefficients =
+                    birth_chain.iter().chain(death_chain.iter().flatten()).map(|&ix| (ix, 1)).collect();
+                interval.birth_chain = birth_chain;
+                interval.death_chain = death_chain;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary_matrix::FilteredBoundaryMatrix;
+    use crate::homology::PersistenceOptions;
+    use crate::point_cloud::PointCloud;
+    use crate::test_utils::circle_cloud;
+    use ndarray::{array, concatenate, Axis};
+
+    fn is_cycle<T: Chain + std::fmt::Debug, C: ChainComplex<T>>(complex: &C, chain: &HashSet<usize>) -> bool {
+        boundary_of(complex, chain.iter().copied()).is_empty()
+    }
+
+    fn length<T: Chain + std::fmt::Debug, C: ChainComplex<T>>(complex: &C, chain: &HashSet<usize>) -> f64 {
+        chain.iter().map(|&ix| complex.filtration_level(ix).into_inner()).sum()
+    }
+
+    #[test]
+    fn test_shortest_cycles() {
+        // Noisy circle with a few points inside
+        let circle = circle_cloud(16, 0.2).points;
+        let inside = array![[0.5, 0.1], [-0.4, 0.3], [0.0, -0.6]];
+        let points = concatenate![Axis(0), circle, inside];
+        let complex = PointCloud::new(points).unwrap().vietoris_rips_complex(2, 1.2).unwrap();
+        let intervals = complex.persistence_intervals();
+
+        let mut found = 0;
+        for interval in &intervals[&1] {
+            if let Some((cycle, death_chain)) = shortest_cycle(&complex, interval) {
+                found += 1;
+                assert!(is_cycle(&complex, &cycle));
+                assert_eq!(cycle.iter().max(), interval.birth_chain.iter().max());
+                assert!(length(&complex, &cycle) <= length(&complex, &interval.birth_chain) + 1e-12);
+                if let Some(death_chain) = death_chain {
+                    assert_eq!(HashSet::from(&boundary_of(&complex, death_chain.into_iter())), cycle);
+                }
+            }
+        }
+        assert!(found > 0);
+    }
+
+    #[test]
+    fn test_volume_optimal_octahedron() {
+        let points = array![
+            [1.0, 0.0, 0.0],
+            [-1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, -1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, -1.0]
+        ];
+        let complex = PointCloud::new(points).unwrap().vietoris_rips_complex(3, 2.0).unwrap();
+        let intervals = complex.persistence_intervals();
+        let void = intervals[&2].iter().find(|i| i.death > i.birth).unwrap();
+
+        let (cycle, volume) = volume_optimal_cycle(&complex, void).unwrap();
+        // The only 2-cycle at birth is the octahedron itself
+        assert_eq!(cycle.len(), 8);
+        assert!(volume.len() <= void.death_chain.as_ref().unwrap().len());
+        assert!(volume.iter().all(|&ix| complex.simplices[ix].dim() == 3));
+        assert!(is_cycle(&complex, &cycle));
+    }
+
+    #[test]
+    fn test_minimal_cycles_option() {
+        let points = array![[0.0, 0.0], [1.0, 0.0], [2.0, 0.1], [2.0, 1.0], [1.0, 1.1], [0.0, 1.0], [1.0, 0.5]];
+        let complex = PointCloud::new(points).unwrap().vietoris_rips_complex(2, 3.0).unwrap();
+        let options = PersistenceOptions { minimal_cycles: true, ..Default::default() };
+        let minimal = complex.persistence_intervals_with_options(&options);
+        let original = complex.persistence_intervals();
+
+        for (dim, ints) in &minimal {
+            for (interval, expected) in ints.iter().zip(&original[dim]) {
+                assert_eq!((interval.birth, interval.death), (expected.birth, expected.death));
+                assert!(is_cycle(&complex, &interval.birth_chain));
+                if *dim == 1 {
+                    assert!(length(&complex, &interval.birth_chain) <= length(&complex, &expected.birth_chain) + 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_loop_edges() {
+        // A triangle and a loop edge at vertex 0, whose boundary is empty over Z/2
+        let complex = FilteredBoundaryMatrix::new(
+            vec![vec![], vec![], vec![], vec![], vec![0, 1], vec![1, 2], vec![0, 2]],
+            vec![0, 0, 0, 1, 1, 1, 1],
+            vec![0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0],
+        )
+        .unwrap();
+        let options = PersistenceOptions { minimal_cycles: true, ..Default::default() };
+        let intervals = complex.persistence_intervals_with_options(&options);
+        assert_eq!(intervals[&1].len(), 2);
+
+        let loop_interval = intervals[&1].iter().find(|i| i.birth == 0.5).unwrap();
+        assert!(shortest_cycle(&complex, loop_interval).is_none());
+        assert_eq!(loop_interval.birth_chain, HashSet::from([3]));
+
+        let triangle = intervals[&1].iter().find(|i| i.birth == 1.0).unwrap();
+        assert_eq!(triangle.birth_chain, HashSet::from([4, 5, 6]));
+        assert!(is_cycle(&complex, &triangle.birth_chain));
+    }
+}
@@ -0,0 +1,223 @@
+use ndarray::Array2;
+use ordered_float::OrderedFloat;
+use std::collections::{BTreeMap, HashMap};
+
+use super::homology::PersistenceInterval;
+
+/// A persistence diagram, i.e. the persistence intervals of a filtration grouped by dimension.
+///
+/// Intervals are sorted by birth, then by death, within each dimension so that the diagram
+/// does not depend on the order in which the reduction produced them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PersistenceDiagram {
+    /// Sorted intervals for each dimension
+    intervals: BTreeMap<usize, Vec<PersistenceInterval>>,
+}
+
+impl PersistenceDiagram {
+    /// Creates a persistence diagram from intervals grouped by dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `intervals` - Intervals per dimension, e.g. as returned by
+    ///   [`ChainComplex::persistence_intervals`](super::homology::ChainComplex::persistence_intervals)
+    ///
+    /// # Returns
+    ///
+    /// A new PersistenceDiagram instance
+    pub fn new(intervals: HashMap<usize, Vec<PersistenceInterval>>) -> Self {
+        let intervals = intervals
+            .into_iter()
+            .map(|(dim, mut ints)| {
+                ints.sort_by_key(|i| (OrderedFloat(i.birth), OrderedFloat(i.death)));
+                (dim, ints)
+            })
+            .collect();
+        Self { intervals }
+    }
+
+    /// Returns the dimensions present in the diagram, in increasing order.
+    pub fn dimensions(&self) -> impl Iterator<Item = usize> + '_ {
+        self.intervals.keys().copied()
+    }
+
+    /// Returns the sorted intervals in the given dimension (empty if there are none).
+    pub fn intervals(&self, dim: usize) -> &[PersistenceInterval] {
+        self.intervals.get(&dim).map_or(&[], |ints| ints.as_slice())
+    }
+
+    /// Returns the total number of intervals across all dimensions.
+    pub fn len(&self) -> usize {
+        self.intervals.values().map(|ints| ints.len()).sum()
+    }
+
+    /// Returns true if the diagram has no intervals.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the intervals in the given dimension as (birth, death) pairs.
+    pub fn pairs(&self, dim: usize) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.intervals(dim).iter().map(|i| (i.birth, i.death))
+    }
+
+    /// Returns the number of features in the given dimension alive at a filtration value,
+    /// i.e. intervals with `birth <= value < death`.
+    pub fn betti_number(&self, dim: usize, value: f64) -> usize {
+        self.intervals(dim).iter().filter(|i| i.birth <= value && value < i.death).count()
+    }
+
+    /// Returns the Betti numbers at a filtration value for every dimension in the diagram.
+    pub fn betti_numbers(&self, value: f64) -> HashMap<usize, usize> {
+        self.dimensions().map(|dim| (dim, self.betti_number(dim, value))).collect()
+    }
+
+    /// Returns the Betti curve in the given dimension, i.e. the Betti number at each grid value.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - Dimension of the features to count
+    /// * `grid` - Filtration values at which to sample the curve
+    pub fn betti_curve(&self, dim: usize, grid: &[f64]) -> Vec<usize> {
+        // Features alive at x are those born at or before x, minus those which also died
+        let mut births: Vec<OrderedFloat<f64>> = self.intervals(dim).iter().map(|i| OrderedFloat(i.birth)).collect();
+        let mut deaths: Vec<OrderedFloat<f64>> = self.intervals(dim).iter().map(|i| OrderedFloat(i.death)).collect();
+        births.sort();
+        deaths.sort();
+        grid.iter()
+            .map(|&x| {
+                births.partition_point(|&b| b <= OrderedFloat(x)) - deaths.partition_point(|&d| d <= OrderedFloat(x))
+            })
+            .collect()
+    }
+
+    /// Returns the Betti curves for every dimension in the diagram.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - Filtration values at which to sample the curves
+    ///
+    /// # Returns
+    ///
+    /// A HashMap where keys are dimensions and values are the Betti numbers at each grid value.
+    pub fn betti_curves(&self, grid: &[f64]) -> HashMap<usize, Vec<usize>> {
+        self.dimensions().map(|dim| (dim, self.betti_curve(dim, grid))).collect()
+    }
+
+    /// Returns a diagram keeping only the intervals whose lifetime (death - birth) is at least
+    /// the given value. Infinite intervals are always kept.
+    pub fn filter_by_lifetime(&self, min_lifetime: f64) -> Self {
+        self.filter(|i| i.death.is_infinite() || i.death - i.birth >= min_lifetime)
+    }
+
+    /// Returns a diagram keeping only the intervals satisfying the predicate.
+    pub fn filter<P: Fn(&PersistenceInterval) -> bool>(&self, predicate: P) -> Self {
+        let intervals = self
+            .intervals
+            .iter()
+            .map(|(&dim, ints)| (dim, ints.iter().filter(|i| predicate(i)).cloned().collect()))
+            .collect();
+        Self { intervals }
+    }
+
+    /// Returns a diagram where infinite deaths are replaced by the given value, e.g. the
+    /// largest filtration level. Representative chains are kept as they are.
+    pub fn truncate_infinite(&self, value: f64) -> Self {
+        let mut intervals = self.intervals.clone();
+        for ints in intervals.values_mut() {
+            for interval in ints.iter_mut().filter(|i| i.death.is_infinite()) {
+                interval.death = value;
+            }
+            ints.sort_by_key(|i| (OrderedFloat(i.birth), OrderedFloat(i.death)));
+        }
+        Self { intervals }
+    }
+
+    /// Returns the intervals in the given dimension as an array with one (birth, death) row
+    /// per interval.
+    pub fn to_array(&self, dim: usize) -> Array2<f64> {
+        let ints = self.intervals(dim);
+        Array2::from_shape_fn((ints.len(), 2), |(ix, jx)| if jx == 0 { ints[ix].birth } else { ints[ix].death })
+    }
+}
+
+impl From<HashMap<usize, Vec<PersistenceInterval>>> for PersistenceDiagram {
+    fn from(intervals: HashMap<usize, Vec<PersistenceInterval>>) -> Self {
+        Self::new(intervals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::homology::ChainComplex;
+    use crate::point_cloud::PointCloud;
+    use crate::test_utils::interval;
+    use ndarray::array;
+    use std::f64::consts::SQRT_2;
+
+    #[test]
+    fn test_sorted_pairs() {
+        let diagram = PersistenceDiagram::new(HashMap::from([
+            (0, vec![interval(0.0, 1.0), interval(0.0, f64::INFINITY), interval(0.0, 0.5)]),
+            (1, vec![interval(2.0, 3.0), interval(1.0, 4.0)]),
+        ]));
+        assert_eq!(diagram.len(), 5);
+        assert_eq!(diagram.dimensions().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(diagram.pairs(0).collect::<Vec<_>>(), vec![(0.0, 0.5), (0.0, 1.0), (0.0, f64::INFINITY)]);
+        assert_eq!(diagram.to_array(1), array![[1.0, 4.0], [2.0, 3.0]]);
+        assert!(diagram.intervals(2).is_empty());
+    }
+
+    #[test]
+    fn test_queries() {
+        let diagram = PersistenceDiagram::new(HashMap::from([
+            (0, vec![interval(0.0, 1.0), interval(0.0, f64::INFINITY), interval(0.0, 0.5)]),
+            (1, vec![interval(2.0, 3.0), interval(1.0, 4.0)]),
+        ]));
+        assert_eq!(diagram.betti_numbers(0.5), HashMap::from([(0, 2), (1, 0)]));
+        assert_eq!(diagram.betti_numbers(2.0), HashMap::from([(0, 1), (1, 2)]));
+
+        let filtered = diagram.filter_by_lifetime(1.0);
+        assert_eq!(filtered.pairs(0).collect::<Vec<_>>(), vec![(0.0, 1.0), (0.0, f64::INFINITY)]);
+        assert_eq!(filtered.pairs(1).collect::<Vec<_>>(), vec![(1.0, 4.0), (2.0, 3.0)]);
+
+        // Intervals born at an infinite level are infinite too
+        let born_at_infinity = PersistenceDiagram::new(HashMap::from([(
+            0,
+            vec![interval(0.0, 0.5), interval(f64::INFINITY, f64::INFINITY)],
+        )]));
+        let filtered = born_at_infinity.filter_by_lifetime(1.0);
+        assert_eq!(filtered.pairs(0).collect::<Vec<_>>(), vec![(f64::INFINITY, f64::INFINITY)]);
+
+        let truncated = diagram.truncate_infinite(0.75);
+        assert_eq!(truncated.pairs(0).collect::<Vec<_>>(), vec![(0.0, 0.5), (0.0, 0.75), (0.0, 1.0)]);
+    }
+
+    #[test]
+    fn test_square_diagram() {
+        let point_cloud = PointCloud::new(array![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]).unwrap();
+        let complex = point_cloud.vietoris_rips_complex(2, 10.0).unwrap();
+        let diagram = complex.persistence_diagram();
+
+        assert_eq!(diagram.to_array(0), array![[0.0, 1.0], [0.0, 1.0], [0.0, 1.0], [0.0, f64::INFINITY]]);
+        assert_eq!(diagram.betti_numbers(0.0), HashMap::from([(0, 4), (1, 0), (2, 0)]));
+        assert_eq!(diagram.betti_numbers(1.0), HashMap::from([(0, 1), (1, 1), (2, 0)]));
+        assert_eq!(diagram.filter_by_lifetime(f64::EPSILON).pairs(1).collect::<Vec<_>>(), vec![(1.0, SQRT_2)]);
+    }
+
+    #[test]
+    fn test_betti_curves() {
+        let diagram = PersistenceDiagram::new(HashMap::from([
+            (0, vec![interval(0.0, 1.0), interval(0.0, f64::INFINITY), interval(0.0, 0.5)]),
+            (1, vec![interval(2.0, 3.0), interval(1.0, 4.0)]),
+        ]));
+        let grid = [-1.0, 0.0, 0.5, 1.0, 2.0, 3.0, 4.0];
+        let curves = diagram.betti_curves(&grid);
+        assert_eq!(curves[&0], vec![0, 3, 2, 1, 1, 1, 1]);
+        assert_eq!(curves[&1], vec![0, 0, 0, 1, 2, 1, 0]);
+        for (ix, &x) in grid.iter().enumerate() {
+            assert_eq!(curves[&1][ix], diagram.betti_number(1, x));
+        }
+    }
+}
@@ -0,0 +1,731 @@
+use log::debug;
+use ndarray::Array2;
+use ordered_float::OrderedFloat;
+use std::collections::{HashMap, VecDeque};
+
+use super::diagram::PersistenceDiagram;
+use super::homology::PersistenceInterval;
+
+/// A matching between two sets of persistence intervals, where intervals that are not matched to
+/// an interval of the other set are matched to the diagonal.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Matching {
+    /// Distance achieved by the matching
+    pub distance: f64,
+    /// Matched pairs of indices into the first and second set of intervals, where None stands
+    /// for the diagonal
+    pub pairs: Vec<(Option<usize>, Option<usize>)>,
+}
+
+/// Returns the L∞ distance between two finite intervals seen as points in the plane.
+fn l_inf(a: &PersistenceInterval, b: &PersistenceInterval) -> f64 {
+    (a.birth - b.birth).abs().max((a.death - b.death).abs())
+}
+
+/// Returns the L∞ distance from a finite interval to the diagonal.
+fn diagonal_distance(a: &PersistenceInterval) -> f64 {
+    (a.death - a.birth) / 2.0
+}
+
+/// Splits intervals into the indices of the finite and the infinite ones.
+fn split(intervals: &[PersistenceInterval]) -> (Vec<usize>, Vec<usize>) {
+    (0..intervals.len()).partition(|&ix| intervals[ix].death.is_finite())
+}
+
+/// Matches infinite intervals by increasing birth, which is optimal for any ground metric on
+/// the line. The distance is infinite if the numbers of infinite intervals differ.
+fn match_essential(
+    a: &[PersistenceInterval],
+    b: &[PersistenceInterval],
+    mut a_essential: Vec<usize>,
+    mut b_essential: Vec<usize>,
+) -> Vec<(Option<usize>, Option<usize>)> {
+    a_essential.sort_by_key(|&ix| OrderedFloat(a[ix].birth));
+    b_essential.sort_by_key(|&ix| OrderedFloat(b[ix].birth));
+
+    let n = a_essential.len().max(b_essential.len());
+    (0..n).map(|ix| (a_essential.get(ix).copied(), b_essential.get(ix).copied())).collect()
+}
+
+/// Returns the cost of an essential pair as the distance between births (infinite if one side
+/// is missing). Equal births cost nothing, even if they are infinite.
+fn essential_cost(a: &[PersistenceInterval], b: &[PersistenceInterval], pair: (Option<usize>, Option<usize>)) -> f64 {
+    match pair {
+        (Some(ix), Some(jx)) if a[ix].birth == b[jx].birth => 0.0,
+        (Some(ix), Some(jx)) => (a[ix].birth - b[jx].birth).abs(),
+        _ => f64::INFINITY,
+    }
+}
+
+/// Finds a maximum matching in a bipartite graph using the Hopcroft-Karp algorithm.
+///
+/// # Arguments
+///
+/// * `adjacency` - Right neighbours of each left vertex
+/// * `n_right` - Number of right vertices
+///
+/// # Returns
+///
+/// The right vertex matched to each left vertex, if any.
+fn maximum_matching(adjacency: &[Vec<usize>], n_right: usize) -> Vec<Option<usize>> {
+    let n_left = adjacency.len();
+    let mut match_left: Vec<Option<usize>> = vec![None; n_left];
+    let mut match_right: Vec<Option<usize>> = vec![None; n_right];
+    let mut layer = vec![usize::MAX; n_left];
+
+    fn augment(
+        u: usize,
+        adjacency: &[Vec<usize>],
+        layer: &mut [usize],
+        match_left: &mut [Option<usize>],
+        match_right: &mut [Option<usize>],
+    ) -> bool {
+        for &v in &adjacency[u] {
+            let next = match_right[v];
+            if next.is_none_or(|w| layer[w] == layer[u] + 1 && augment(w, adjacency, layer, match_left, match_right)) {
+                match_left[u] = Some(v);
+                match_right[v] = Some(u);
+                return true;
+            }
+        }
+        // Dead end, don't visit again in this phase
+        layer[u] = usize::MAX;
+        false
+    }
+
+    loop {
+        // Breadth first search from free left vertices, layering alternating paths
+        let mut queue = VecDeque::new();
+        for u in 0..n_left {
+            if match_left[u].is_none() {
+                layer[u] = 0;
+                queue.push_back(u);
+            } else {
+                layer[u] = usize::MAX;
+            }
+        }
+        let mut found = false;
+        while let Some(u) = queue.pop_front() {
+            for &v in &adjacency[u] {
+                match match_right[v] {
+                    Some(w) if layer[w] == usize::MAX => {
+                        layer[w] = layer[u] + 1;
+                        queue.push_back(w);
+                    },
+                    None => found = true,
+                    _ => {},
+                }
+            }
+        }
+        if !found {
+            break;
+        }
+
+        // Augment along vertex-disjoint shortest paths
+        for u in 0..n_left {
+            if match_left[u].is_none() {
+                augment(u, adjacency, &mut layer, &mut match_left, &mut match_right);
+            }
+        }
+    }
+
+    match_left
+}
+
+/// Computes the bottleneck distance between two sets of persistence intervals together with an
+/// optimal matching.
+///
+/// Finite intervals are matched to each other or to the diagonal, with L∞ ground distance.
+/// Infinite intervals can only be matched to each other, at the distance between their births,
+/// so the distance is infinite if the numbers of infinite intervals differ. The distance is
+/// found exactly by a binary search over the candidate edge lengths, testing for a perfect
+/// matching in the threshold graph with the Hopcroft-Karp algorithm.
+///
+/// # Arguments
+///
+/// * `a` - First set of intervals (typically a single dimension of a diagram)
+/// * `b` - Second set of intervals
+///
+/// # Returns
+///
+/// A Matching whose pairs index into `a` and `b`.
+pub fn bottleneck_matching(a: &[PersistenceInterval], b: &[PersistenceInterval]) -> Matching {
+    let (a_finite, a_essential) = split(a);
+    let (b_finite, b_essential) = split(b);
+    let mut pairs = match_essential(a, b, a_essential, b_essential);
+    let essential_distance = pairs.iter().map(|&pair| essential_cost(a, b, pair)).fold(0.0, f64::max);
+
+    // Augmented bipartite graph: left is a then the diagonal projections of b, right is b then
+    // the diagonal projections of a
+    let (n, m) = (a_finite.len(), b_finite.len());
+    let cost = |ix: usize, jx: usize| -> Option<f64> {
+        match (ix < n, jx < m) {
+            (true, true) => Some(l_inf(&a[a_finite[ix]], &b[b_finite[jx]])),
+            (true, false) => (jx - m == ix).then(|| diagonal_distance(&a[a_finite[ix]])),
+            (false, true) => (ix - n == jx).then(|| diagonal_distance(&b[b_finite[jx]])),
+            (false, false) => Some(0.0),
+        }
+    };
+
+    let mut candidates: Vec<OrderedFloat<f64>> =
+        (0..n + m).flat_map(|ix| (0..n + m).filter_map(move |jx| cost(ix, jx))).map(OrderedFloat).collect();
+    candidates.sort();
+    candidates.dedup();
+    debug!("Bottleneck search over {} candidate distances", candidates.len());
+
+    let perfect_matching = |threshold: f64| -> Option<Vec<Option<usize>>> {
+        let adjacency: Vec<Vec<usize>> = (0..n + m)
+            .map(|ix| (0..n + m).filter(|&jx| cost(ix, jx).is_some_and(|c| c <= threshold)).collect())
+            .collect();
+        let matching = maximum_matching(&adjacency, n + m);
+        matching.iter().all(Option::is_some).then_some(matching)
+    };
+
+    // The largest candidate always admits a perfect matching (everything to the diagonal)
+    let (mut lo, mut hi) = (0, candidates.len().saturating_sub(1));
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if perfect_matching(candidates[mid].into_inner()).is_some() {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    let threshold = candidates.get(lo).map_or(0.0, |c| c.into_inner());
+
+    if let Some(matching) = perfect_matching(threshold) {
+        for (ix, jx) in matching.into_iter().map(Option::unwrap).enumerate() {
+            match (ix < n, jx < m) {
+                (true, true) => pairs.push((Some(a_finite[ix]), Some(b_finite[jx]))),
+                (true, false) => pairs.push((Some(a_finite[ix]), None)),
+                (false, true) => pairs.push((None, Some(b_finite[jx]))),
+                (false, false) => {},
+            }
+        }
+    }
+
+    Matching { distance: threshold.max(essential_distance), pairs }
+}
+
+/// Computes the bottleneck distance between two sets of persistence intervals.
+///
+/// See [`bottleneck_matching`] for details.
+///
+/// # Arguments
+///
+/// * `a` - First set of intervals (typically a single dimension of a diagram)
+/// * `b` - Second set of intervals
+///
+/// # Returns
+///
+/// The bottleneck distance, which is infinite if the numbers of infinite intervals differ.
+pub fn bottleneck_distance(a: &[PersistenceInterval], b: &[PersistenceInterval]) -> f64 {
+    bottleneck_matching(a, b).distance
+}
+
+/// Computes the bottleneck distance between two persistence diagrams in every dimension present
+/// in either of them.
+///
+/// # Arguments
+///
+/// * `a` - First persistence diagram
+/// * `b` - Second persistence diagram
+///
+/// # Returns
+///
+/// A HashMap where keys are dimensions and values are bottleneck distances.
+pub fn bottleneck_distances(a: &PersistenceDiagram, b: &PersistenceDiagram) -> HashMap<usize, f64> {
+    a.dimensions()
+        .chain(b.dimensions())
+        .map(|dim| (dim, bottleneck_distance(a.intervals(dim), b.intervals(dim))))
+        .collect()
+}
+
+/// Ground metric used to measure distances between points of persistence diagrams.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GroundMetric {
+    /// L∞ distance in the (birth, death) plane
+    #[default]
+    LInf,
+    /// Euclidean distance in the (birth, death) plane
+    L2,
+}
+
+impl GroundMetric {
+    /// Returns the distance between two finite intervals seen as points in the plane.
+    fn distance(&self, a: &PersistenceInterval, b: &PersistenceInterval) -> f64 {
+        match self {
+            GroundMetric::LInf => l_inf(a, b),
+            GroundMetric::L2 => (a.birth - b.birth).hypot(a.death - b.death),
+        }
+    }
+
+    /// Returns the distance from a finite interval to the diagonal.
+    fn diagonal_distance(&self, a: &PersistenceInterval) -> f64 {
+        match self {
+            GroundMetric::LInf => diagonal_distance(a),
+            GroundMetric::L2 => (a.death - a.birth) / std::f64::consts::SQRT_2,
+        }
+    }
+}
+
+/// Options controlling the computation of Wasserstein distances.
+#[derive(Clone, Debug)]
+pub struct WassersteinOptions {
+    /// Ground metric between points of the diagrams
+    pub metric: GroundMetric,
+    /// Exponent q of the distance, at least 1 (the bottleneck distance is the limit q → ∞)
+    pub exponent: f64,
+    /// Largest number of finite intervals in both diagrams combined for which the assignment
+    /// problem is solved exactly with the Hungarian algorithm. Larger problems use the auction
+    /// algorithm.
+    pub exact_max_size: usize,
+    /// Relative error allowed in the sum of q-th powers of the matching costs when the auction
+    /// algorithm is used
+    pub relative_error: f64,
+}
+
+impl Default for WassersteinOptions {
+    fn default() -> Self {
+        Self { metric: GroundMetric::default(), exponent: 1.0, exact_max_size: 200, relative_error: 1e-4 }
+    }
+}
+
+/// Solves the (square) assignment problem exactly using the Hungarian algorithm with potentials.
+///
+/// # Returns
+///
+/// The column assigned to each row in a minimum cost assignment.
+fn hungarian(cost: &Array2<f64>) -> Vec<usize> {
+    let n = cost.nrows();
+    // Potentials and matching are 1-indexed, with column 0 a virtual column
+    let mut u = vec![0.0; n + 1];
+    let mut v = vec![0.0; n + 1];
+    let mut row_of = vec![0; n + 1];
+    let mut way = vec![0; n + 1];
+
+    for ix in 1..=n {
+        row_of[0] = ix;
+        let mut j0 = 0;
+        let mut min_slack = vec![f64::INFINITY; n + 1];
+        let mut used = vec![false; n + 1];
+        loop {
+            used[j0] = true;
+            let i0 = row_of[j0];
+            let mut delta = f64::INFINITY;
+            let mut j1 = 0;
+            for jx in 1..=n {
+                if !used[jx] {
+                    let slack = cost[[i0 - 1, jx - 1]] - u[i0] - v[jx];
+                    if slack < min_slack[jx] {
+                        min_slack[jx] = slack;
+                        way[jx] = j0;
+                    }
+                    if min_slack[jx] < delta {
+                        delta = min_slack[jx];
+                        j1 = jx;
+                    }
+                }
+            }
+            for jx in 0..=n {
+                if used[jx] {
+                    u[row_of[jx]] += delta;
+                    v[jx] -= delta;
+                } else {
+                    min_slack[jx] -= delta;
+                }
+            }
+            j0 = j1;
+            if row_of[j0] == 0 {
+                break;
+            }
+        }
+        // Flip the augmenting path
+        while j0 != 0 {
+            let j1 = way[j0];
+            row_of[j0] = row_of[j1];
+            j0 = j1;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for jx in 1..=n {
+        assignment[row_of[jx] - 1] = jx - 1;
+    }
+    assignment
+}
+
+/// Solves the (square) assignment problem approximately using the auction algorithm with
+/// epsilon scaling.
+///
+/// Each round ends with an assignment whose cost is within `n * epsilon` of the optimum, so
+/// epsilon is reduced until that bound is within the relative error of the cost.
+///
+/// # Returns
+///
+/// The column assigned to each row.
+fn auction(cost: &Array2<f64>, relative_error: f64) -> Vec<usize> {
+    let n = cost.nrows();
+    let max_cost = cost.iter().copied().fold(0.0, f64::max);
+    if max_cost == 0.0 {
+        // Every assignment is optimal, and no bid would ever raise a price
+        return (0..n).collect();
+    }
+    let min_epsilon = (max_cost * f64::EPSILON).max(f64::MIN_POSITIVE);
+    let mut prices = vec![0.0; n];
+    let mut epsilon = (max_cost / 4.0).max(min_epsilon);
+    let mut round = 0;
+
+    loop {
+        let mut owner: Vec<Option<usize>> = vec![None; n];
+        let mut assignment: Vec<Option<usize>> = vec![None; n];
+        let mut unassigned: VecDeque<usize> = (0..n).collect();
+
+        while let Some(ix) = unassigned.pop_front() {
+            // Find the best and second best objects for this bidder (minimizing cost + price)
+            let (mut best, mut best_value, mut second_value) = (0, f64::INFINITY, f64::INFINITY);
+            for jx in 0..n {
+                let value = cost[[ix, jx]] + prices[jx];
+                if value < best_value {
+                    (second_value, best_value, best) = (best_value, value, jx);
+                } else if value < second_value {
+                    second_value = value;
+                }
+            }
+            let increment = if second_value.is_finite() { second_value - best_value } else { 0.0 };
+            prices[best] += increment + epsilon;
+
+            if let Some(previous) = owner[best].replace(ix) {
+                assignment[previous] = None;
+                unassigned.push_back(previous);
+            }
+            assignment[ix] = Some(best);
+        }
+
+        let assignment: Vec<usize> = assignment.into_iter().map(Option::unwrap).collect();
+        let total: f64 = assignment.iter().enumerate().map(|(ix, &jx)| cost[[ix, jx]]).sum();
+        let bound = n as f64 * epsilon;
+        round += 1;
+        debug!("Auction round {} with epsilon {} has cost {}", round, epsilon, total);
+        if total == 0.0 || bound <= relative_error * (total - bound) || epsilon <= min_epsilon {
+            return assignment;
+        }
+        epsilon = (epsilon / 5.0).max(min_epsilon);
+    }
+}
+
+/// Computes the q-Wasserstein distance between two sets of persistence intervals together with
+/// an optimal (or, for large diagrams, near-optimal) matching.
+///
+/// Finite intervals are matched to each other or to the diagonal, and the distance is the q-th
+/// root of the sum of q-th powers of the ground distances of matched points. Infinite intervals
+/// can only be matched to each other, at the distance between their births, so the distance is
+/// infinite if the numbers of infinite intervals differ. Small problems are solved exactly with
+/// the Hungarian algorithm, larger ones with the auction algorithm up to the configured
+/// relative error.
+///
+/// # Arguments
+///
+/// * `a` - First set of intervals (typically a single dimension of a diagram)
+/// * `b` - Second set of intervals
+/// * `options` - Options controlling the computation
+///
+/// # Returns
+///
+/// A Matching whose pairs index into `a` and `b`.
+///
+/// # Panics
+///
+/// Panics if the exponent is less than 1.
+pub fn wasserstein_matching(
+    a: &[PersistenceInterval],
+    b: &[PersistenceInterval],
+    options: &WassersteinOptions,
+) -> Matching {
+    assert!(options.exponent >= 1.0, "Wasserstein exponent must be at least 1");
+    let q = options.exponent;
+
+    let (a_finite, a_essential) = split(a);
+    let (b_finite, b_essential) = split(b);
+    let mut pairs = match_essential(a, b, a_essential, b_essential);
+    let essential_cost: f64 = pairs.iter().map(|&pair| essential_cost(a, b, pair).powf(q)).sum();
+
+    // Augmented cost matrix: rows are a then the diagonal slots of b, columns are b then the
+    // diagonal slots of a. Diagonal slots are interchangeable, so any slot can be used.
+    let (n, m) = (a_finite.len(), b_finite.len());
+    let cost = Array2::from_shape_fn((n + m, n + m), |(ix, jx)| match (ix < n, jx < m) {
+        (true, true) => options.metric.distance(&a[a_finite[ix]], &b[b_finite[jx]]).powf(q),
+        (true, false) => options.metric.diagonal_distance(&a[a_finite[ix]]).powf(q),
+        (false, true) => options.metric.diagonal_distance(&b[b_finite[jx]]).powf(q),
+        (false, false) => 0.0,
+    });
+
+    let assignment =
+        if n + m <= options.exact_max_size { hungarian(&cost) } else { auction(&cost, options.relative_error) };
+    let finite_cost: f64 = assignment.iter().enumerate().map(|(ix, &jx)| cost[[ix, jx]]).sum();
+
+    for (ix, jx) in assignment.into_iter().enumerate() {
+        match (ix < n, jx < m) {
+            (true, true) => pairs.push((Some(a_finite[ix]), Some(b_finite[jx]))),
+            (true, false) => pairs.push((Some(a_finite[ix]), None)),
+            (false, true) => pairs.push((None, Some(b_finite[jx]))),
+            (false, false) => {},
+        }
+    }
+
+    Matching { distance: (essential_cost + finite_cost).powf(1.0 / q), pairs }
+}
+
+/// Computes the q-Wasserstein distance between two sets of persistence intervals.
+///
+/// See [`wasserstein_matching`] for details.
+///
+/// # Arguments
+///
+/// * `a` - First set of intervals (typically a single dimension of a diagram)
+/// * `b` - Second set of intervals
+/// * `options` - Options controlling the computation
+///
+/// # Returns
+///
+/// The Wasserstein distance, which is infinite if the numbers of infinite intervals differ.
+pub fn wasserstein_distance(a: &[PersistenceInterval], b: &[PersistenceInterval], options: &WassersteinOptions) -> f64 {
+    wasserstein_matching(a, b, options).distance
+}
+
+/// Computes the q-Wasserstein distance between two persistence diagrams in every dimension
+/// present in either of them.
+///
+/// # Arguments
+///
+/// * `a` - First persistence diagram
+/// * `b` - Second persistence diagram
+/// * `options` - Options controlling the computation
+///
+/// # Returns
+///
+/// A HashMap where keys are dimensions and values are Wasserstein distances.
+pub fn wasserstein_distances(
+    a: &PersistenceDiagram,
+    b: &PersistenceDiagram,
+    options: &WassersteinOptions,
+) -> HashMap<usize, f64> {
+    a.dimensions()
+        .chain(b.dimensions())
+        .map(|dim| (dim, wasserstein_distance(a.intervals(dim), b.intervals(dim), options)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::homology::ChainComplex;
+    use crate::point_cloud::PointCloud;
+    use crate::test_utils::{interval, intervals, random_cloud, random_numbers};
+    use std::f64::consts::SQRT_2;
+
+    fn random_intervals(n: usize, seed: u64) -> Vec<PersistenceInterval> {
+        let values = random_numbers(2 * n, seed);
+        (0..n).map(|ix| interval(values[2 * ix], values[2 * ix] + values[2 * ix + 1])).collect()
+    }
+
+    /// Bottleneck distance by trying every assignment in the augmented graph.
+    fn brute_force_bottleneck(a: &[PersistenceInterval], b: &[PersistenceInterval]) -> f64 {
+        let (n, m) = (a.len(), b.len());
+        let cost = |ix: usize, jx: usize| match (ix < n, jx < m) {
+            (true, true) => l_inf(&a[ix], &b[jx]),
+            (true, false) if jx - m == ix => diagonal_distance(&a[ix]),
+            (false, true) if ix - n == jx => diagonal_distance(&b[jx]),
+            (false, false) => 0.0,
+            _ => f64::INFINITY,
+        };
+
+        fn search(ix: usize, used: &mut Vec<bool>, cost: &dyn Fn(usize, usize) -> f64) -> f64 {
+            if ix == used.len() {
+                return 0.0;
+            }
+            let mut best = f64::INFINITY;
+            for jx in 0..used.len() {
+                if !used[jx] {
+                    used[jx] = true;
+                    best = best.min(cost(ix, jx).max(search(ix + 1, used, cost)));
+                    used[jx] = false;
+                }
+            }
+            best
+        }
+        search(0, &mut vec![false; n + m], &cost)
+    }
+
+    #[test]
+    fn test_simple_distances() {
+        let a = intervals(&[(0.0, 2.0)]);
+        assert_eq!(bottleneck_distance(&a, &[]), 1.0);
+        assert_eq!(bottleneck_distance(&a, &intervals(&[(0.0, 3.0)])), 1.0);
+        assert_eq!(bottleneck_distance(&a, &intervals(&[(4.0, 10.0)])), 3.0);
+        assert_eq!(bottleneck_distance(&a, &a), 0.0);
+        assert_eq!(bottleneck_distance(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn test_infinite_intervals() {
+        let a = intervals(&[(0.0, f64::INFINITY), (0.0, 1.0)]);
+        let b = intervals(&[(0.5, f64::INFINITY)]);
+        let matching = bottleneck_matching(&a, &b);
+        assert_eq!(matching.distance, 0.5);
+        assert_eq!(matching.pairs, vec![(Some(0), Some(0)), (Some(1), None)]);
+
+        let c = intervals(&[(0.0, f64::INFINITY), (1.0, f64::INFINITY)]);
+        assert_eq!(bottleneck_distance(&b, &c), f64::INFINITY);
+
+        // Intervals born at infinity match each other at no cost
+        let d = intervals(&[(0.5, f64::INFINITY), (f64::INFINITY, f64::INFINITY)]);
+        assert_eq!(bottleneck_distance(&d, &d), 0.0);
+        assert_eq!(wasserstein_distance(&d, &d, &WassersteinOptions::default()), 0.0);
+    }
+
+    #[test]
+    fn test_matching() {
+        let a = intervals(&[(0.0, 4.0), (1.0, 1.2)]);
+        let b = intervals(&[(0.1, 4.2)]);
+        let matching = bottleneck_matching(&a, &b);
+        assert!((matching.distance - 0.2).abs() < 1e-12);
+        assert_eq!(matching.pairs.len(), 2);
+        assert!(matching.pairs.contains(&(Some(0), Some(0))));
+        assert!(matching.pairs.contains(&(Some(1), None)));
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        for seed in 0..20 {
+            let a = random_intervals(1 + seed as usize % 3, seed);
+            let b = random_intervals(1 + seed as usize % 4, seed + 100);
+            let expected = brute_force_bottleneck(&a, &b);
+            assert_eq!(bottleneck_distance(&a, &b), expected);
+            assert_eq!(bottleneck_distance(&b, &a), expected);
+        }
+    }
+
+    #[test]
+    fn test_stability() {
+        // Moving every point by at most epsilon changes Rips diagrams by at most 2 epsilon
+        let epsilon = 0.01;
+        let values = random_numbers(40, 3);
+        let noise = random_numbers(40, 4);
+        let points = Array2::from_shape_vec((20, 2), values).unwrap();
+        let perturbed = &points + &Array2::from_shape_vec((20, 2), noise).unwrap().mapv(|x| epsilon * (x - 0.5));
+
+        let a = PointCloud::new(points).unwrap().vietoris_rips_complex(2, 10.0).unwrap().persistence_diagram();
+        let b = PointCloud::new(perturbed).unwrap().vietoris_rips_complex(2, 10.0).unwrap().persistence_diagram();
+        let distances = bottleneck_distances(&a, &b);
+        assert_eq!(distances.len(), 3);
+        for distance in distances.values() {
+            assert!(*distance <= 2.0 * epsilon);
+        }
+    }
+
+    /// Minimum assignment cost by trying every permutation.
+    fn brute_force_assignment(cost: &Array2<f64>) -> f64 {
+        fn search(ix: usize, used: &mut Vec<bool>, cost: &Array2<f64>) -> f64 {
+            if ix == used.len() {
+                return 0.0;
+            }
+            let mut best = f64::INFINITY;
+            for jx in 0..used.len() {
+                if !used[jx] {
+                    used[jx] = true;
+                    best = best.min(cost[[ix, jx]] + search(ix + 1, used, cost));
+                    used[jx] = false;
+                }
+            }
+            best
+        }
+        search(0, &mut vec![false; cost.nrows()], cost)
+    }
+
+    fn assignment_cost(cost: &Array2<f64>, assignment: &[usize]) -> f64 {
+        assignment.iter().enumerate().map(|(ix, &jx)| cost[[ix, jx]]).sum()
+    }
+
+    #[test]
+    fn test_assignment_solvers() {
+        for seed in 0..10 {
+            let n = 2 + seed as usize % 5;
+            let cost = Array2::from_shape_vec((n, n), random_numbers(n * n, seed)).unwrap();
+            let expected = brute_force_assignment(&cost);
+            assert!((assignment_cost(&cost, &hungarian(&cost)) - expected).abs() < 1e-12);
+
+            let approximate = assignment_cost(&cost, &auction(&cost, 1e-6));
+            assert!(approximate >= expected - 1e-12 && approximate <= expected * (1.0 + 1e-6) + 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_simple_wasserstein() {
+        let a = intervals(&[(0.0, 2.0)]);
+        let b = intervals(&[(0.0, 3.0)]);
+
+        let options = WassersteinOptions::default();
+        assert_eq!(wasserstein_distance(&a, &[], &options), 1.0);
+        assert_eq!(wasserstein_distance(&a, &b, &options), 1.0);
+        assert_eq!(wasserstein_distance(&a, &a, &options), 0.0);
+
+        let options = WassersteinOptions { metric: GroundMetric::L2, exponent: 2.0, ..Default::default() };
+        assert!((wasserstein_distance(&a, &[], &options) - SQRT_2).abs() < 1e-12);
+        assert_eq!(wasserstein_distance(&a, &b, &options), 1.0);
+
+        // Two small intervals are cheaper to send to the diagonal than to match to each other
+        let c = intervals(&[(0.0, 0.2), (5.0, 5.2)]);
+        let matching = wasserstein_matching(&c[..1], &c[1..], &WassersteinOptions::default());
+        assert!((matching.distance - 0.2).abs() < 1e-12);
+        assert_eq!(matching.pairs, vec![(Some(0), None), (None, Some(0))]);
+    }
+
+    #[test]
+    fn test_wasserstein_infinite_intervals() {
+        let options = WassersteinOptions { exponent: 2.0, ..Default::default() };
+        let a = intervals(&[(0.0, f64::INFINITY), (1.0, f64::INFINITY), (0.0, 1.0)]);
+        let b = intervals(&[(3.0, f64::INFINITY), (0.0, f64::INFINITY)]);
+        // Births 0, 1 are matched to 0, 3 and the finite interval goes to the diagonal
+        let expected = (0.0 + 4.0 + 0.25_f64).sqrt();
+        assert!((wasserstein_distance(&a, &b, &options) - expected).abs() < 1e-12);
+        assert_eq!(wasserstein_distance(&a[1..], &b, &options), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_auction_matches_exact() {
+        let a = random_intervals(40, 1);
+        let b = random_intervals(30, 2);
+        for metric in [GroundMetric::LInf, GroundMetric::L2] {
+            for exponent in [1.0, 2.0] {
+                let exact = WassersteinOptions { metric, exponent, ..Default::default() };
+                let approximate = WassersteinOptions { exact_max_size: 0, relative_error: 1e-6, ..exact.clone() };
+                let expected = wasserstein_distance(&a, &b, &exact);
+                let distance = wasserstein_distance(&a, &b, &approximate);
+                assert!(distance >= expected - 1e-12 && distance <= expected * (1.0 + 1e-6));
+            }
+        }
+    }
+
+    #[test]
+    fn test_auction_zero_costs() {
+        assert_eq!(auction(&Array2::zeros((3, 3)), 1e-4), vec![0, 1, 2]);
+
+        // Zero-length intervals only have zero costs
+        let options = WassersteinOptions { exact_max_size: 0, ..Default::default() };
+        let a = intervals(&[(1.0, 1.0), (1.0, 1.0)]);
+        assert_eq!(wasserstein_distance(&a, &a[..1], &options), 0.0);
+    }
+
+    #[test]
+    fn test_wasserstein_dominates_bottleneck() {
+        let a = random_intervals(10, 5);
+        let b = random_intervals(8, 6);
+        let options = WassersteinOptions { exponent: 2.0, ..Default::default() };
+        assert!(wasserstein_distance(&a, &b, &options) >= bottleneck_distance(&a, &b) - 1e-12);
+
+        let diagram = random_cloud(10, 7).vietoris_rips_complex(1, 10.0).unwrap().persistence_diagram();
+        let distances = wasserstein_distances(&diagram, &diagram, &options);
+        assert_eq!(distances, HashMap::from([(0, 0.0), (1, 0.0)]));
+    }
+}
@@ -0,0 +1,190 @@
+use thiserror::Error;
+
+/// Error types for field operations
+#[derive(Error, Debug)]
+pub enum FieldError {
+    #[error("Modulus {0} is not prime")]
+    NotPrime(u64),
+    #[error("Modulus {0} is too large (must be less than 2^32)")]
+    TooLarge(u64),
+}
+
+/// A finite field of coefficients used for chain computations.
+///
+/// Elements are represented by their canonical representatives `0..characteristic`.
+pub trait Field {
+    /// Returns the characteristic of the field.
+    fn characteristic(&self) -> u64;
+
+    /// Returns the sum of two elements.
+    fn add(&self, a: u64, b: u64) -> u64;
+
+    /// Returns the additive inverse of an element.
+    fn neg(&self, a: u64) -> u64;
+
+    /// Returns the product of two elements.
+    fn mul(&self, a: u64, b: u64) -> u64;
+
+    /// Returns the multiplicative inverse of a non-zero element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is zero.
+    fn inv(&self, a: u64) -> u64;
+
+    /// Returns the element represented by an integer (i.e. its residue).
+    fn element(&self, a: i64) -> u64 {
+        a.rem_euclid(self.characteristic() as i64) as u64
+    }
+
+    /// Returns the difference of two elements.
+    fn sub(&self, a: u64, b: u64) -> u64 {
+        self.add(a, self.neg(b))
+    }
+}
+
+/// The field with two elements, Z/2, where orientation is irrelevant.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Z2;
+
+impl Field for Z2 {
+    fn characteristic(&self) -> u64 {
+        2
+    }
+
+    fn add(&self, a: u64, b: u64) -> u64 {
+        a ^ b
+    }
+
+    fn neg(&self, a: u64) -> u64 {
+        a
+    }
+
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        a & b
+    }
+
+    fn inv(&self, a: u64) -> u64 {
+        if a == 0 {
+            panic!("Zero has no inverse in Z/2");
+        }
+        1
+    }
+
+    fn element(&self, a: i64) -> u64 {
+        (a & 1) as u64
+    }
+}
+
+/// The prime field Z/p for a prime p chosen at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrimeField {
+    /// The prime modulus
+    p: u64,
+}
+
+impl PrimeField {
+    /// Creates the field Z/p.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Prime modulus
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the field or an error if:
+    /// - The modulus is not prime
+    /// - The modulus is too large for products to be computed without overflow
+    pub fn new(p: u64) -> Result<Self, FieldError> {
+        if p >= 1 << 32 {
+            return Err(FieldError::TooLarge(p));
+        }
+        if p < 2 || (2..).take_while(|d| d * d <= p).any(|d| p.is_multiple_of(d)) {
+            return Err(FieldError::NotPrime(p));
+        }
+        Ok(Self { p })
+    }
+}
+
+impl Default for PrimeField {
+    /// Returns Z/2.
+    fn default() -> Self {
+        Self { p: 2 }
+    }
+}
+
+impl Field for PrimeField {
+    fn characteristic(&self) -> u64 {
+        self.p
+    }
+
+    fn add(&self, a: u64, b: u64) -> u64 {
+        (a + b) % self.p
+    }
+
+    fn neg(&self, a: u64) -> u64 {
+        (self.p - a) % self.p
+    }
+
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        (a * b) % self.p
+    }
+
+    fn inv(&self, a: u64) -> u64 {
+        if a.is_multiple_of(self.p) {
+            panic!("Zero has no inverse in Z/{}", self.p);
+        }
+
+        // Extended Euclidean algorithm
+        let (mut r0, mut r1) = (self.p as i64, (a % self.p) as i64);
+        let (mut t0, mut t1) = (0i64, 1i64);
+        while r1 != 0 {
+            let q = r0 / r1;
+            (r0, r1) = (r1, r0 - q * r1);
+            (t0, t1) = (t1, t0 - q * t1);
+        }
+        self.element(t0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prime_field_creation() {
+        assert!(PrimeField::new(2).is_ok());
+        assert!(PrimeField::new(7).is_ok());
+        assert!(PrimeField::new(0).is_err());
+        assert!(PrimeField::new(1).is_err());
+        assert!(PrimeField::new(9).is_err());
+        assert!(PrimeField::new(1 << 32).is_err());
+        assert_eq!(PrimeField::default().characteristic(), 2);
+    }
+
+    #[test]
+    fn test_prime_field_arithmetic() {
+        let field = PrimeField::new(7).unwrap();
+        assert_eq!(field.add(5, 4), 2);
+        assert_eq!(field.sub(2, 5), 4);
+        assert_eq!(field.neg(0), 0);
+        assert_eq!(field.mul(3, 5), 1);
+        assert_eq!(field.element(-1), 6);
+        for a in 1..7 {
+            assert_eq!(field.mul(a, field.inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn test_z2_matches_prime_field() {
+        let field = PrimeField::default();
+        for a in 0..2 {
+            for b in 0..2 {
+                assert_eq!(Z2.add(a, b), field.add(a, b));
+                assert_eq!(Z2.mul(a, b), field.mul(a, b));
+            }
+        }
+        assert_eq!(Z2.element(-1), field.element(-1));
+        assert_eq!(Z2.inv(1), field.inv(1));
+    }
+}
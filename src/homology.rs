@@ -3,15 +3,12 @@ use ndarray::Array2;
 use ordered_float::OrderedFloat;
 use std::collections::{HashMap, HashSet};
 
-/// Performs symmetric difference (XOR) operation between two sets over Z/2.
-/// Modifies the first set in place.
-fn xor(a: &mut HashSet<usize>, b: &HashSet<usize>) {
-    for &y in b {
-        if !a.insert(y) {
-            a.remove(&y);
-        }
-    }
-}
+use super::cycles::minimize_cycles;
+use super::diagram::PersistenceDiagram;
+use super::field::{Field, PrimeField, Z2};
+use super::integral::{integral_homology, HomologyGroup};
+use super::reduction::{persistent_cohomology, persistent_homology, PersistencePair, SparseColumn};
+use super::sparse::SparseBoundaryMatrix;
 
 /// Represents a persistence interval in the filtration.
 ///
@@ -21,42 +18,110 @@ fn xor(a: &mut HashSet<usize>, b: &HashSet<usize>) {
 pub struct PersistenceInterval {
     /// Value at which the feature appears
     pub birth: f64,
-    /// Chain representing the birth of the feature
+    /// Chain representing the birth of the feature (a cocycle when computed via cohomology,
+    /// empty if representatives were not computed)
     pub birth_chain: HashSet<usize>,
     /// Value at which the feature disappears (infinity if the feature persists)
     pub death: f64,
-    /// Chain representing the death of the feature (None if the feature persists)
+    /// Chain representing the death of the feature (None if the feature persists or
+    /// representatives were not computed). When computed via cohomology, this is the
+    /// coboundary of the representative cocycle.
     pub death_chain: Option<HashSet<usize>>,
+    /// Coefficients of the elements of the birth and death chains in the field used for the
+    /// computation (all 1 over Z/2)
+    pub coefficients: HashMap<usize, u64>,
+}
+
+/// Matrix reduction used to compute persistence intervals.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PersistenceAlgorithm {
+    /// Reduce the boundary matrix (persistent homology)
+    #[default]
+    Homology,
+    /// Reduce the coboundary matrix (persistent cohomology). This is typically much faster for
+    /// Vietoris-Rips filtrations and yields cocycle representatives.
+    Cohomology,
 }
 
-/// Internal representation of a complex entry used during persistence computation.
+/// Options controlling the computation of persistence intervals.
 #[derive(Clone, Debug)]
-struct TableEntry {
-    /// Index of the parent chain that kills this cycle
-    parent: usize,
-    /// Indicates if this represents a cycle that persists to the next dimension
-    represents_cycle: bool,
-    /// Elements in the pivot column (chains that this bounds)
-    co_bounds: HashSet<usize>,
-    /// Chains that this element bounds
-    bound: HashSet<usize>,
-    /// Chains collected with this basis element during reduction
-    chain: HashSet<usize>,
+pub struct PersistenceOptions {
+    /// Matrix reduction used to compute the intervals
+    pub algorithm: PersistenceAlgorithm,
+    /// Field of coefficients (Z/2 by default)
+    pub field: PrimeField,
+    /// Whether to detect apparent pairs up front and skip reducing their columns (enabled by
+    /// default). This does not change the result, and is most effective for Vietoris-Rips
+    /// filtrations, where most pairs are apparent.
+    pub apparent_pairs: bool,
+    /// Which intervals to compute representative chains for
+    pub representatives: Representatives,
+    /// Whether to replace representatives by shortest cycles (dimension 1, with edges weighted
+    /// by filtration level) and volume-optimal cycles (higher dimensions), see
+    /// [`shortest_cycle`](super::cycles::shortest_cycle) and
+    /// [`volume_optimal_cycle`](super::cycles::volume_optimal_cycle). Only applies to
+    /// homology over Z/2 (disabled by default).
+    pub minimal_cycles: bool,
 }
 
-impl TableEntry {
-    /// Creates a new TableEntry initialized with the given index in its chain.
-    fn new(index: usize) -> Self {
+impl Default for PersistenceOptions {
+    fn default() -> Self {
         Self {
-            parent: 0, // TODO
-            represents_cycle: false,
-            co_bounds: HashSet::new(),
-            bound: HashSet::new(),
-            chain: HashSet::from([index]),
+            algorithm: PersistenceAlgorithm::default(),
+            field: PrimeField::default(),
+            apparent_pairs: true,
+            representatives: Representatives::default(),
+            minimal_cycles: false,
         }
     }
 }
 
+/// Which persistence intervals representative chains are computed for.
+///
+/// Intervals without representatives have an empty birth chain and no death chain.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Representatives {
+    /// Compute representatives for every interval
+    #[default]
+    All,
+    /// Only compute birth and death values, without tracking chains during the reduction
+    None,
+    /// Compute representatives only for intervals whose persistence (death - birth) is strictly
+    /// greater than the threshold, which includes all infinite intervals
+    AboveThreshold(f64),
+}
+
+/// A persistence pair together with its (birth chain, death chain) representatives, if computed.
+type PairWithRepresentatives = (PersistencePair, Option<(SparseColumn, Option<SparseColumn>)>);
+
+/// Computes persistence pairs of a boundary matrix with the given options and field, along with
+/// representatives for the pairs selected by the options.
+fn persistence_pairs<F: Field>(
+    boundaries: Vec<SparseColumn>,
+    dims: &[usize],
+    levels: &[f64],
+    options: &PersistenceOptions,
+    field: &F,
+) -> Vec<PairWithRepresentatives> {
+    let reduction = match options.algorithm {
+        PersistenceAlgorithm::Homology => persistent_homology(boundaries, dims, options, field),
+        PersistenceAlgorithm::Cohomology => persistent_cohomology(boundaries, dims, options, field),
+    };
+
+    let wanted = |pair: &PersistencePair| match options.representatives {
+        Representatives::All => true,
+        Representatives::None => false,
+        Representatives::AboveThreshold(threshold) => {
+            pair.death.is_none_or(|death| levels[death] - levels[pair.birth] > threshold)
+        },
+    };
+    let selected: Vec<PersistencePair> = reduction.pairs.iter().copied().filter(wanted).collect();
+    let mut representatives =
+        if selected.is_empty() { Vec::new() } else { reduction.representatives(&selected, field) }.into_iter();
+
+    reduction.pairs.iter().map(|pair| (*pair, if wanted(pair) { representatives.next() } else { None })).collect()
+}
+
 /// Represents a chain in a chain complex.
 ///
 /// A chain is a formal sum of basis elements in a given dimension.
@@ -85,6 +150,16 @@ pub trait ChainComplex<T: Chain + std::fmt::Debug> {
     /// not tracked.
     fn boundary(&self, index: usize) -> HashSet<usize>;
 
+    /// Returns the oriented boundary of the chain at the given index.
+    ///
+    /// The boundary is represented as (index, coefficient) pairs over the integers,
+    /// as needed for computations over fields other than Z/2. By default every
+    /// element of the boundary has coefficient 1, which is only correct over Z/2,
+    /// so complexes with an orientation should override this.
+    fn signed_boundary(&self, index: usize) -> Vec<(usize, i64)> {
+        self.boundary(index).into_iter().map(|ix| (ix, 1)).collect()
+    }
+
     /// Returns the number of chains in the complex.
     fn len(&self) -> usize {
         self.chains().len()
@@ -115,7 +190,8 @@ pub trait ChainComplex<T: Chain + std::fmt::Debug> {
     /// The boundary matrix represents the boundary operator as a matrix where
     /// entry (i,j) is 1 if chain i appears in the boundary of chain j (working over Z/2).
     ///
-    /// Note: This does not include filtration information.
+    /// Note: This does not include filtration information, and allocates a dense n×n matrix, so
+    /// [`Self::sparse_boundary_matrix`] should be preferred for large complexes.
     fn boundary_matrix(&self) -> Array2<usize> {
         let n = self.len();
         let mut matrix = Array2::<usize>::zeros((n, n));
@@ -127,103 +203,500 @@ pub trait ChainComplex<T: Chain + std::fmt::Debug> {
         matrix
     }
 
-    /// Removes pivot rows from the boundary of a chain.
+    /// Computes the oriented boundary matrix of the chain complex in compressed sparse column
+    /// form, with the dimension and filtration level of each column.
     ///
-    /// This is a key step in the persistence algorithm that performs the matrix reduction
-    /// to compute persistent homology. It modifies the table entries in place.
-    ///
-    /// # Arguments
+    /// The coboundary matrix is obtained with [`SparseBoundaryMatrix::coboundary`], and the
+    /// coordinate form with [`SparseBoundaryMatrix::to_coo`].
+    fn sparse_boundary_matrix(&self) -> SparseBoundaryMatrix {
+        SparseBoundaryMatrix::from_complex(self)
+    }
+
+    /// Compute the homology groups of the chain complex over the integers.
     ///
-    /// * `chain_ix` - Index of the chain whose boundary we're processing
-    /// * `table` - Mutable reference to the table of entries being built
+    /// This ignores the filtration and uses the Smith normal form of the oriented
+    /// boundary maps, so that torsion is detected.
     ///
     /// # Returns
     ///
-    /// The reduced boundary after removing pivot rows
-    #[allow(private_interfaces)] // TODO
-    fn remove_pivot_rows(&self, chain_ix: usize, table: &mut Vec<TableEntry>) -> HashSet<usize> {
-        let mut boundary = self.boundary(chain_ix);
-
-        // Only keep boundary chains that generate cycles
-        boundary.retain(|&bx| table[bx].represents_cycle);
-
-        // Reduce the boundary by performing column operations
-        while let Some(&max_index) = boundary.iter().max() {
-            if table[max_index].co_bounds.is_empty() {
-                // This row is unclaimed - use as pivot
-                break;
-            } else {
-                // Perform column operation
-                xor(&mut boundary, &table[max_index].co_bounds);
-                // Add chains from the parent entry
-                let other = table[table[max_index].parent].chain.clone();
-                table[chain_ix].chain.extend(&other);
-            }
-        }
-
-        boundary
+    /// A HashMap where keys are dimensions and values are the homology groups (rank,
+    /// torsion coefficients and generators) in that dimension.
+    fn integral_homology(&self) -> HashMap<usize, HomologyGroup> {
+        integral_homology(self)
     }
 
     /// Compute persistence intervals for the chain complex.
     ///
     /// This implements the standard persistence algorithm to compute the intervals
-    /// where homological features (connected components, loops, voids, etc.) exist.
+    /// where homological features (connected components, loops, voids, etc.) exist,
+    /// using the default [`PersistenceOptions`].
     ///
     /// # Returns
     ///
     /// A HashMap where keys are dimensions and values are vectors of persistence intervals
     /// in that dimension.
     fn persistence_intervals(&self) -> HashMap<usize, Vec<PersistenceInterval>> {
-        let mut table: Vec<TableEntry> = (0..self.len()).map(TableEntry::new).collect();
+        self.persistence_intervals_with_options(&PersistenceOptions::default())
+    }
+
+    /// Compute the Euler characteristic curve of the filtration, i.e. the alternating sum of
+    /// the number of chains of each dimension present at each grid value.
+    ///
+    /// This only counts chains, so it is much cheaper than computing persistence and can be
+    /// used as a summary when the reduction is infeasible.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - Filtration values at which to sample the curve
+    ///
+    /// # Returns
+    ///
+    /// The Euler characteristic of the subcomplex at each grid value.
+    fn euler_characteristic_curve(&self, grid: &[f64]) -> Vec<i64> {
+        let mut contributions: Vec<(OrderedFloat<f64>, i64)> = (0..self.len())
+            .map(|ix| (self.filtration_level(ix), if self.chain(ix).dim() % 2 == 0 { 1 } else { -1 }))
+            .collect();
+        contributions.sort();
+
+        // Cumulative Euler characteristic after each chain
+        let cumulative: Vec<i64> = contributions
+            .iter()
+            .scan(0, |chi, &(_, sign)| {
+                *chi += sign;
+                Some(*chi)
+            })
+            .collect();
+        grid.iter()
+            .map(|&x| match contributions.partition_point(|&(level, _)| level <= OrderedFloat(x)) {
+                0 => 0,
+                n => cumulative[n - 1],
+            })
+            .collect()
+    }
+
+    /// Compute the persistence diagram of the chain complex, i.e. the persistence intervals
+    /// sorted deterministically within each dimension.
+    ///
+    /// # Returns
+    ///
+    /// A PersistenceDiagram computed with the default [`PersistenceOptions`].
+    fn persistence_diagram(&self) -> PersistenceDiagram {
+        PersistenceDiagram::new(self.persistence_intervals())
+    }
+
+    /// Compute persistence intervals for the chain complex with the given options.
+    ///
+    /// The boundary (or coboundary) matrix is reduced using sorted sparse columns, one
+    /// dimension at a time, with columns already known to be cycles cleared rather than
+    /// reduced. The column operations are recorded (unless representatives are disabled) so
+    /// that representative chains can be reconstructed for the requested intervals. Over
+    /// fields other than Z/2 the oriented boundary is used and the representatives carry
+    /// coefficients.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Options controlling the computation
+    ///
+    /// # Returns
+    ///
+    /// A HashMap where keys are dimensions and values are vectors of persistence intervals
+    /// in that dimension.
+    fn persistence_intervals_with_options(
+        &self,
+        options: &PersistenceOptions,
+    ) -> HashMap<usize, Vec<PersistenceInterval>> {
+        let dims: Vec<usize> = self.chains().iter().map(|s| s.dim()).collect();
+        let levels: Vec<f64> = (0..self.len()).map(|ix| self.filtration_level(ix).into_inner()).collect();
+        let pairs = if options.field.characteristic() == 2 {
+            // Orientation is irrelevant, and Z/2 arithmetic is cheaper
+            let boundaries: Vec<SparseColumn> =
+                (0..self.len()).map(|ix| SparseColumn::from(self.boundary(ix))).collect();
+            persistence_pairs(boundaries, &dims, &levels, options, &Z2)
+        } else {
+            let boundaries: Vec<SparseColumn> = (0..self.len())
+                .map(|ix| SparseColumn::from_entries(self.signed_boundary(ix), &options.field))
+                .collect();
+            persistence_pairs(boundaries, &dims, &levels, options, &options.field)
+        };
 
         // Initialize intervals map for each dimension up to max dimension
-        let max_dim = self.chains().iter().map(|s| s.dim()).max().unwrap_or(0);
+        let max_dim = dims.iter().copied().max().unwrap_or(0);
         let mut intervals: HashMap<usize, Vec<PersistenceInterval>> = (0..=max_dim).map(|i| (i, Vec::new())).collect();
 
-        // Process each chain in the complex
-        for chain_ix in 0..self.len() {
-            let boundary = self.remove_pivot_rows(chain_ix, &mut table);
+        for (pair, representatives) in pairs {
+            let birth = self.filtration_level(pair.birth);
+            let death = match pair.death {
+                Some(death_ix) => {
+                    debug!("Creating interval: birth={}, death={}", birth, self.filtration_level(death_ix));
+                    self.filtration_level(death_ix).into_inner()
+                },
+                None => {
+                    debug!("Creating infinite interval: birth={}", birth);
+                    f64::INFINITY
+                },
+            };
+
+            let (birth_chain, death_chain) = representatives.unwrap_or_default();
+            let mut coefficients = HashMap::from(&birth_chain);
+            if let Some(death_chain) = &death_chain {
+                coefficients.extend(HashMap::from(death_chain));
+            }
+
+            intervals.get_mut(&dims[pair.birth]).unwrap().push(PersistenceInterval {
+                birth: birth.into_inner(),
+                birth_chain: HashSet::from(&birth_chain),
+                death,
+                death_chain: death_chain.as_ref().map(HashSet::from),
+                coefficients,
+            });
+        }
+
+        if options.minimal_cycles
+            && options.algorithm == PersistenceAlgorithm::Homology
+            && options.field.characteristic() == 2
+        {
+            minimize_cycles(self, &mut intervals);
+        }
+
+        intervals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point_cloud::PointCloud;
+    use crate::simplicial_complex::{Simplex, SimplicialComplex};
+    use crate::test_utils::random_cloud;
+    use ndarray::array;
+
+    /// Performs symmetric difference (XOR) operation between two sets over Z/2.
+    /// Modifies the first set in place.
+    fn xor(a: &mut HashSet<usize>, b: &HashSet<usize>) {
+        for &y in b {
+            if !a.insert(y) {
+                a.remove(&y);
+            }
+        }
+    }
+
+    /// Entry of the table used by the reference (pivot-row) algorithm.
+    #[derive(Clone, Debug)]
+    struct TableEntry {
+        parent: usize,
+        represents_cycle: bool,
+        co_bounds: HashSet<usize>,
+        chain: HashSet<usize>,
+    }
+
+    /// Reference implementation of the persistence algorithm of Zomorodian and Carlsson using
+    /// hash sets, against which the sparse reduction is cross-checked.
+    fn reference_persistence_intervals<T: Chain + std::fmt::Debug>(
+        complex: &impl ChainComplex<T>,
+    ) -> HashMap<usize, Vec<PersistenceInterval>> {
+        let mut table: Vec<TableEntry> = (0..complex.len())
+            .map(|ix| TableEntry {
+                parent: 0,
+                represents_cycle: false,
+                co_bounds: HashSet::new(),
+                chain: HashSet::from([ix]),
+            })
+            .collect();
+        let max_dim = complex.chains().iter().map(|s| s.dim()).max().unwrap_or(0);
+        let mut intervals: HashMap<usize, Vec<PersistenceInterval>> = (0..=max_dim).map(|i| (i, Vec::new())).collect();
+
+        for chain_ix in 0..complex.len() {
+            let mut boundary = complex.boundary(chain_ix);
+            boundary.retain(|&bx| table[bx].represents_cycle);
+            while let Some(&max_index) = boundary.iter().max() {
+                if table[max_index].co_bounds.is_empty() {
+                    break;
+                }
+                xor(&mut boundary, &table[max_index].co_bounds);
+                let other = table[table[max_index].parent].chain.clone();
+                table[chain_ix].chain.extend(&other);
+            }
 
             if boundary.is_empty() {
-                // This chain creates a new cycle
                 table[chain_ix].represents_cycle = true;
             } else if let Some(&max_boundary_index) = boundary.iter().max() {
-                // This chain kills an existing cycle
                 table[max_boundary_index].co_bounds = boundary;
                 table[max_boundary_index].parent = chain_ix;
-                table[max_boundary_index].bound.insert(chain_ix);
-
-                let dim = self.chain(max_boundary_index).dim();
-                debug!(
-                    "Creating interval: birth={}, death={}",
-                    self.filtration_level(max_boundary_index),
-                    self.filtration_level(chain_ix)
-                );
-
-                intervals.get_mut(&dim).unwrap().push(PersistenceInterval {
-                    birth: self.filtration_level(max_boundary_index).into_inner(),
+                intervals.get_mut(&complex.chain(max_boundary_index).dim()).unwrap().push(PersistenceInterval {
+                    birth: complex.filtration_level(max_boundary_index).into_inner(),
                     birth_chain: table[max_boundary_index].chain.clone(),
-                    death: self.filtration_level(chain_ix).into_inner(),
+                    death: complex.filtration_level(chain_ix).into_inner(),
                     death_chain: Some(table[chain_ix].chain.clone()),
+                    coefficients: table[max_boundary_index]
+                        .chain
+                        .union(&table[chain_ix].chain)
+                        .map(|&ix| (ix, 1))
+                        .collect(),
                 });
             }
         }
 
-        // Process remaining cycles (those that never die)
         for (ix, entry) in table.iter().enumerate() {
             if entry.represents_cycle && entry.co_bounds.is_empty() {
-                let dim = self.chain(ix).dim();
-                debug!("Creating infinite interval: birth={}", self.filtration_level(ix));
-
-                intervals.get_mut(&dim).unwrap().push(PersistenceInterval {
-                    birth: self.filtration_level(ix).into_inner(),
+                intervals.get_mut(&complex.chain(ix).dim()).unwrap().push(PersistenceInterval {
+                    birth: complex.filtration_level(ix).into_inner(),
                     birth_chain: entry.chain.clone(),
                     death: f64::INFINITY,
                     death_chain: None,
+                    coefficients: entry.chain.iter().map(|&ix| (ix, 1)).collect(),
                 });
             }
         }
 
         intervals
     }
+
+    /// Returns the (birth, death) pairs per dimension, sorted.
+    fn pairs(intervals: &HashMap<usize, Vec<PersistenceInterval>>) -> HashMap<usize, Vec<(f64, f64)>> {
+        intervals
+            .iter()
+            .map(|(dim, ints)| {
+                let mut pairs: Vec<(f64, f64)> = ints.iter().map(|i| (i.birth, i.death)).collect();
+                pairs.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+                (*dim, pairs)
+            })
+            .collect()
+    }
+
+    fn paper_example() -> SimplicialComplex {
+        let simplices = vec![
+            Simplex::new(vec![0]),
+            Simplex::new(vec![1]),
+            Simplex::new(vec![2]),
+            Simplex::new(vec![3]),
+            Simplex::new(vec![0, 1]),
+            Simplex::new(vec![1, 2]),
+            Simplex::new(vec![2, 3]),
+            Simplex::new(vec![0, 3]),
+            Simplex::new(vec![0, 2]),
+            Simplex::new(vec![0, 1, 2]),
+            Simplex::new(vec![0, 2, 3]),
+        ];
+        let levels = vec![0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 3.0, 4.0, 5.0];
+        SimplicialComplex::new(simplices, levels).unwrap()
+    }
+
+    #[test]
+    fn test_cross_check_paper_example() {
+        let complex = paper_example();
+        assert_eq!(complex.persistence_intervals(), reference_persistence_intervals(&complex));
+    }
+
+    #[test]
+    fn test_cross_check_square() {
+        let point_cloud = PointCloud::new(array![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]).unwrap();
+        let complex = point_cloud.vietoris_rips_complex(2, 10.0).unwrap();
+        assert_eq!(complex.persistence_intervals(), reference_persistence_intervals(&complex));
+    }
+
+    #[test]
+    fn test_cross_check_random_clouds() {
+        for seed in 0..5 {
+            let complex = random_cloud(12, seed).vietoris_rips_complex(2, 0.6).unwrap();
+            let intervals = complex.persistence_intervals();
+            assert_eq!(pairs(&intervals), pairs(&reference_persistence_intervals(&complex)));
+
+            // Representatives must be cycles
+            for ints in intervals.values() {
+                for interval in ints {
+                    let mut boundary = HashSet::new();
+                    for &ix in &interval.birth_chain {
+                        xor(&mut boundary, &complex.boundary(ix));
+                    }
+                    assert!(boundary.is_empty());
+                }
+            }
+        }
+    }
+
+    /// Returns the (birth, death) values per dimension, in the order they were reported.
+    fn values(intervals: &HashMap<usize, Vec<PersistenceInterval>>) -> HashMap<usize, Vec<(f64, f64)>> {
+        intervals.iter().map(|(dim, ints)| (*dim, ints.iter().map(|i| (i.birth, i.death)).collect())).collect()
+    }
+
+    /// Asserts that the birth chains of cohomology intervals are cocycles until death.
+    fn assert_cocycles(complex: &SimplicialComplex, intervals: &HashMap<usize, Vec<PersistenceInterval>>) {
+        for (dim, ints) in intervals {
+            for interval in ints {
+                for (ix, simplex) in complex.simplices.iter().enumerate() {
+                    if simplex.dim() == dim + 1 && complex.levels[ix] < interval.death {
+                        let overlap = complex.boundary(ix).intersection(&interval.birth_chain).count();
+                        assert_eq!(overlap % 2, 0, "{:?} is not a cocycle on {:?}", interval.birth_chain, simplex);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cohomology_paper_example() {
+        let complex = paper_example();
+        let options = PersistenceOptions { algorithm: PersistenceAlgorithm::Cohomology, ..Default::default() };
+        let intervals = complex.persistence_intervals_with_options(&options);
+
+        assert_eq!(values(&intervals), values(&complex.persistence_intervals()));
+        assert_cocycles(&complex, &intervals);
+
+        // The loop 0-1-2-3 born at 2.0 is represented by the cocycle on its birth edge 23, which
+        // comes after 03 at the same level
+        let edge = complex.index_of(&Simplex::new(vec![2, 3])).unwrap();
+        let interval = intervals[&1].iter().find(|i| i.birth == 2.0).unwrap();
+        assert_eq!(interval.birth_chain, HashSet::from([edge]));
+    }
+
+    #[test]
+    fn test_cohomology_random_clouds() {
+        let options = PersistenceOptions { algorithm: PersistenceAlgorithm::Cohomology, ..Default::default() };
+        for seed in 0..5 {
+            let complex = random_cloud(12, seed).vietoris_rips_complex(2, 0.6).unwrap();
+            let intervals = complex.persistence_intervals_with_options(&options);
+            assert_eq!(values(&intervals), values(&complex.persistence_intervals()));
+            assert_cocycles(&complex, &intervals);
+        }
+    }
+
+    /// Returns the minimal 6-vertex triangulation of the real projective plane, with vertices,
+    /// edges and triangles appearing at levels 0, 1 and 2 respectively.
+    fn projective_plane() -> SimplicialComplex {
+        let triangles = [
+            [0, 1, 2],
+            [0, 2, 3],
+            [0, 3, 4],
+            [0, 4, 5],
+            [0, 1, 5],
+            [1, 2, 4],
+            [2, 3, 5],
+            [1, 3, 4],
+            [2, 4, 5],
+            [1, 3, 5],
+        ];
+        let mut simplices: HashSet<Vec<usize>> = HashSet::new();
+        for triangle in triangles {
+            for size in 1..=3 {
+                for face in crate::combinatorics::generate_combinations(&triangle, size).unwrap() {
+                    simplices.insert(face);
+                }
+            }
+        }
+        let simplices: Vec<Simplex> = simplices.into_iter().map(Simplex::new).collect();
+        let levels = simplices.iter().map(|s| s.dim() as f64).collect();
+        SimplicialComplex::new(simplices, levels).unwrap()
+    }
+
+    /// Returns the number of infinite intervals in each dimension.
+    fn betti_numbers(intervals: &HashMap<usize, Vec<PersistenceInterval>>) -> Vec<usize> {
+        (0..intervals.len()).map(|dim| intervals[&dim].iter().filter(|i| i.death.is_infinite()).count()).collect()
+    }
+
+    #[test]
+    fn test_projective_plane_coefficients() {
+        let complex = projective_plane();
+        assert_eq!(complex.len(), 31);
+
+        // Over Z/2 the projective plane has the homology of a non-orientable surface
+        let intervals = complex.persistence_intervals();
+        assert_eq!(betti_numbers(&intervals), vec![1, 1, 1]);
+
+        // Over Z/3 the torsion in H_1 is invisible
+        for algorithm in [PersistenceAlgorithm::Homology, PersistenceAlgorithm::Cohomology] {
+            let options = PersistenceOptions { algorithm, field: PrimeField::new(3).unwrap(), ..Default::default() };
+            let intervals = complex.persistence_intervals_with_options(&options);
+            assert_eq!(betti_numbers(&intervals), vec![1, 0, 0]);
+            assert_eq!(values(&intervals)[&1], vec![(1.0, 2.0); 10]);
+        }
+    }
+
+    #[test]
+    fn test_prime_field_representatives() {
+        let field = PrimeField::new(5).unwrap();
+        let options = PersistenceOptions { field, ..Default::default() };
+        for seed in 0..3 {
+            let complex = random_cloud(10, seed).vietoris_rips_complex(2, 0.7).unwrap();
+            let intervals = complex.persistence_intervals_with_options(&options);
+            assert_eq!(pairs(&intervals), pairs(&complex.persistence_intervals()));
+
+            // Representatives must be cycles over Z/5
+            for ints in intervals.values() {
+                for interval in ints {
+                    let mut boundary: HashMap<usize, u64> = HashMap::new();
+                    for &ix in &interval.birth_chain {
+                        for (face, sign) in complex.signed_boundary(ix) {
+                            let term = field.mul(interval.coefficients[&ix], field.element(sign));
+                            let entry = boundary.entry(face).or_default();
+                            *entry = field.add(*entry, term);
+                        }
+                    }
+                    assert!(boundary.values().all(|&c| c == 0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_apparent_pairs_do_not_change_result() {
+        for algorithm in [PersistenceAlgorithm::Homology, PersistenceAlgorithm::Cohomology] {
+            let with_apparent = PersistenceOptions { algorithm, ..Default::default() };
+            let without = PersistenceOptions { algorithm, apparent_pairs: false, ..Default::default() };
+            for seed in 0..5 {
+                let complex = random_cloud(12, seed).vietoris_rips_complex(2, 0.6).unwrap();
+                assert_eq!(
+                    complex.persistence_intervals_with_options(&with_apparent),
+                    complex.persistence_intervals_with_options(&without)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_representatives_options() {
+        let complex = random_cloud(12, 7).vietoris_rips_complex(2, 0.6).unwrap();
+        let all = complex.persistence_intervals();
+
+        for algorithm in [PersistenceAlgorithm::Homology, PersistenceAlgorithm::Cohomology] {
+            let options =
+                PersistenceOptions { algorithm, representatives: Representatives::None, ..Default::default() };
+            let intervals = complex.persistence_intervals_with_options(&options);
+            assert_eq!(values(&intervals), values(&all));
+            assert!(intervals.values().flatten().all(|i| i.birth_chain.is_empty() && i.death_chain.is_none()));
+        }
+
+        let threshold = 0.1;
+        let options =
+            PersistenceOptions { representatives: Representatives::AboveThreshold(threshold), ..Default::default() };
+        let intervals = complex.persistence_intervals_with_options(&options);
+        for (dim, ints) in &intervals {
+            for (interval, expected) in ints.iter().zip(&all[dim]) {
+                if interval.death - interval.birth > threshold {
+                    assert_eq!(interval, expected);
+                } else {
+                    assert!(interval.birth_chain.is_empty());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_euler_characteristic_curve() {
+        let complex = paper_example();
+        let grid = [-1.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        assert_eq!(complex.euler_characteristic_curve(&grid), vec![0, 2, 2, 0, -1, 0, 1, 1]);
+
+        // Euler-Poincaré formula, with all simplices of the Rips complex up to dimension 2
+        let complex = random_cloud(12, 3).vietoris_rips_complex(2, 0.6).unwrap();
+        let grid: Vec<f64> = (0..30).map(|ix| 0.02 * ix as f64).collect();
+        let curves = complex.persistence_diagram().betti_curves(&grid);
+        let euler = complex.euler_characteristic_curve(&grid);
+        for (ix, chi) in euler.into_iter().enumerate() {
+            let alternating: i64 = curves
+                .iter()
+                .map(|(dim, curve)| if dim % 2 == 0 { curve[ix] as i64 } else { -(curve[ix] as i64) })
+                .sum();
+            assert_eq!(chi, alternating);
+        }
+    }
 }
@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+
+use super::homology::{Chain, ChainComplex};
+use ndarray::{s, Array2};
+
+/// Smith normal form `S = U A V` of an integer matrix `A`.
+///
+/// `U` and `V` are unimodular (invertible over the integers) and `S` is diagonal, with each
+/// non-zero diagonal entry dividing the next.
+#[derive(Clone, Debug)]
+pub struct SmithNormalForm {
+    /// Non-zero diagonal entries of `S` (the invariant factors), in order
+    pub diagonal: Vec<i64>,
+    /// Row transformation `U`
+    pub left: Array2<i64>,
+    /// Inverse of the row transformation `U`
+    pub left_inverse: Array2<i64>,
+    /// Column transformation `V`
+    pub right: Array2<i64>,
+    /// Inverse of the column transformation `V`
+    pub right_inverse: Array2<i64>,
+}
+
+impl SmithNormalForm {
+    /// Returns the rank of the matrix.
+    pub fn rank(&self) -> usize {
+        self.diagonal.len()
+    }
+}
+
+/// Working state of the Smith normal form computation, applying every elementary operation to
+/// the matrix and to the transformations tracking it.
+struct SmithReduction {
+    matrix: Array2<i64>,
+    left: Array2<i64>,
+    left_inverse: Array2<i64>,
+    right: Array2<i64>,
+    right_inverse: Array2<i64>,
+}
+
+impl SmithReduction {
+    /// Swaps rows `i` and `j`.
+    fn swap_rows(&mut self, i: usize, j: usize) {
+        if i != j {
+            for mut m in [self.matrix.view_mut(), self.left.view_mut()] {
+                for k in 0..m.ncols() {
+                    m.swap([i, k], [j, k]);
+                }
+            }
+            for k in 0..self.left_inverse.nrows() {
+                self.left_inverse.swap([k, i], [k, j]);
+            }
+        }
+    }
+
+    /// Swaps columns `i` and `j`.
+    fn swap_cols(&mut self, i: usize, j: usize) {
+        if i != j {
+            for mut m in [self.matrix.view_mut(), self.right.view_mut()] {
+                for k in 0..m.nrows() {
+                    m.swap([k, i], [k, j]);
+                }
+            }
+            for k in 0..self.right_inverse.ncols() {
+                self.right_inverse.swap([i, k], [j, k]);
+            }
+        }
+    }
+
+    /// Adds `factor` times row `source` to row `target`.
+    fn add_row(&mut self, target: usize, source: usize, factor: i64) {
+        for mut m in [self.matrix.view_mut(), self.left.view_mut()] {
+            for k in 0..m.ncols() {
+                m[[target, k]] += factor * m[[source, k]];
+            }
+        }
+        for k in 0..self.left_inverse.nrows() {
+            self.left_inverse[[k, source]] -= factor * self.left_inverse[[k, target]];
+        }
+    }
+
+    /// Adds `factor` times column `source` to column `target`.
+    fn add_col(&mut self, target: usize, source: usize, factor: i64) {
+        for mut m in [self.matrix.view_mut(), self.right.view_mut()] {
+            for k in 0..m.nrows() {
+                m[[k, target]] += factor * m[[k, source]];
+            }
+        }
+        for k in 0..self.right_inverse.ncols() {
+            self.right_inverse[[source, k]] -= factor * self.right_inverse[[target, k]];
+        }
+    }
+
+    /// Negates row `i`.
+    fn negate_row(&mut self, i: usize) {
+        self.matrix.row_mut(i).mapv_inplace(|x| -x);
+        self.left.row_mut(i).mapv_inplace(|x| -x);
+        self.left_inverse.column_mut(i).mapv_inplace(|x| -x);
+    }
+
+    /// Moves the smallest non-zero entry of the trailing submatrix to position (t, t).
+    ///
+    /// Returns false if the trailing submatrix is zero.
+    fn move_smallest_to_pivot(&mut self, t: usize) -> bool {
+        let smallest = self
+            .matrix
+            .slice(s![t.., t..])
+            .indexed_iter()
+            .filter(|(_, &x)| x != 0)
+            .min_by_key(|(_, &x)| x.abs())
+            .map(|((i, j), _)| (i + t, j + t));
+        match smallest {
+            Some((i, j)) => {
+                self.swap_rows(t, i);
+                self.swap_cols(t, j);
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+/// Computes the Smith normal form of an integer matrix.
+///
+/// Entries are reduced by repeatedly moving the smallest remaining entry to the diagonal and
+/// eliminating its row and column by Euclidean division, so intermediate entries stay small for
+/// the sparse, unit-entry matrices arising as boundary matrices.
+///
+/// # Arguments
+///
+/// * `matrix` - Integer matrix to reduce
+pub fn smith_normal_form(matrix: &Array2<i64>) -> SmithNormalForm {
+    let (m, n) = matrix.dim();
+    let mut state = SmithReduction {
+        matrix: matrix.clone(),
+        left: Array2::eye(m),
+        left_inverse: Array2::eye(m),
+        right: Array2::eye(n),
+        right_inverse: Array2::eye(n),
+    };
+
+    let mut diagonal = Vec::new();
+    for t in 0..m.min(n) {
+        if !state.move_smallest_to_pivot(t) {
+            break;
+        }
+
+        loop {
+            // Eliminate the pivot column and row, restarting whenever a smaller remainder appears
+            let pivot = state.matrix[[t, t]];
+            let mut done = true;
+            for i in t + 1..m {
+                let q = state.matrix[[i, t]].div_euclid(pivot);
+                if q != 0 {
+                    state.add_row(i, t, -q);
+                }
+                done &= state.matrix[[i, t]] == 0;
+            }
+            for j in t + 1..n {
+                let q = state.matrix[[t, j]].div_euclid(pivot);
+                if q != 0 {
+                    state.add_col(j, t, -q);
+                }
+                done &= state.matrix[[t, j]] == 0;
+            }
+            if !done {
+                state.move_smallest_to_pivot(t);
+                continue;
+            }
+
+            // The pivot must divide every remaining entry
+            let pivot = state.matrix[[t, t]];
+            let indivisible = state
+                .matrix
+                .slice(s![t + 1.., t + 1..])
+                .indexed_iter()
+                .find(|(_, &x)| x % pivot != 0)
+                .map(|((i, _), _)| i + t + 1);
+            match indivisible {
+                Some(i) => state.add_row(t, i, 1),
+                None => break,
+            }
+        }
+
+        if state.matrix[[t, t]] < 0 {
+            state.negate_row(t);
+        }
+        diagonal.push(state.matrix[[t, t]]);
+    }
+
+    SmithNormalForm {
+        diagonal,
+        left: state.left,
+        left_inverse: state.left_inverse,
+        right: state.right,
+        right_inverse: state.right_inverse,
+    }
+}
+
+/// A homology group over the integers, `Z^rank + Z/t_1 + ... + Z/t_k`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HomologyGroup {
+    /// Rank of the free part (the Betti number)
+    pub rank: usize,
+    /// Torsion coefficients `t_i > 1`, each dividing the next
+    pub torsion: Vec<i64>,
+    /// Cycles generating the free part, as maps from chain index to coefficient
+    pub generators: Vec<HashMap<usize, i64>>,
+    /// Cycles generating the torsion part, one per torsion coefficient
+    pub torsion_generators: Vec<HashMap<usize, i64>>,
+}
+
+/// Computes the homology groups over the integers of a chain complex.
+///
+/// For each dimension `d`, a basis of the cycles is read off the Smith normal form of the
+/// boundary map from dimension `d`, and the boundaries from dimension `d + 1` are expressed in
+/// this basis. The Smith normal form of the result gives the invariant factors (1 for trivial
+/// classes, `t > 1` for torsion, 0 for free classes) together with adapted generators.
+///
+/// The filtration is ignored, so to compute the homology at a given filtration level the
+/// complex should first be truncated.
+///
+/// # Returns
+///
+/// A HashMap where keys are dimensions and values are the homology groups in that dimension.
+pub fn integral_homology<T, C>(complex: &C) -> HashMap<usize, HomologyGroup>
+where
+    T: Chain + std::fmt::Debug,
+    C: ChainComplex<T> + ?Sized,
+{
+    let max_dim = complex.chains().iter().map(|c| c.dim()).max().unwrap_or(0);
+
+    // Position of each chain among the chains of its dimension
+    let mut by_dim: Vec<Vec<usize>> = vec![Vec::new(); max_dim + 2];
+    let mut position = vec![0; complex.len()];
+    for (ix, chain) in complex.chains().iter().enumerate() {
+        position[ix] = by_dim[chain.dim()].len();
+        by_dim[chain.dim()].push(ix);
+    }
+
+    // Boundary map from dimension d to dimension d - 1
+    let boundary_block = |d: usize| -> Array2<i64> {
+        let rows = if d == 0 { 0 } else { by_dim[d - 1].len() };
+        let mut block = Array2::<i64>::zeros((rows, by_dim[d].len()));
+        if d > 0 {
+            for (col, &ix) in by_dim[d].iter().enumerate() {
+                for (face, coefficient) in complex.signed_boundary(ix) {
+                    block[[position[face], col]] += coefficient;
+                }
+            }
+        }
+        block
+    };
+
+    let mut groups = HashMap::new();
+    for (d, chains) in by_dim.iter().enumerate().take(max_dim + 1) {
+        // Cycles are the columns of V beyond the rank of the boundary map
+        let snf = smith_normal_form(&boundary_block(d));
+        let rank = snf.rank();
+        let cycles = snf.right.slice(s![.., rank..]).to_owned();
+
+        // Express boundaries in the basis of cycles and reduce them
+        let boundaries = snf.right_inverse.dot(&boundary_block(d + 1)).slice(s![rank.., ..]).to_owned();
+        let quotient = smith_normal_form(&boundaries);
+        let basis = cycles.dot(&quotient.left_inverse);
+
+        let as_chain = |k: usize| -> HashMap<usize, i64> {
+            basis.column(k).iter().enumerate().filter(|(_, &c)| c != 0).map(|(i, &c)| (chains[i], c)).collect()
+        };
+
+        let mut group = HomologyGroup::default();
+        for k in 0..basis.ncols() {
+            match quotient.diagonal.get(k) {
+                Some(1) => {},
+                Some(&t) => {
+                    group.torsion.push(t);
+                    group.torsion_generators.push(as_chain(k));
+                },
+                None => {
+                    group.rank += 1;
+                    group.generators.push(as_chain(k));
+                },
+            }
+        }
+        groups.insert(d, group);
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplicial_complex::{Simplex, SimplicialComplex};
+    use ndarray::array;
+
+    /// Builds a simplicial complex from its maximal simplices, with all simplices at level 0.
+    fn complex_from_facets(facets: &[Vec<usize>]) -> SimplicialComplex {
+        let mut simplices = std::collections::HashSet::new();
+        for facet in facets {
+            for subset in crate::combinatorics::generate_subsets(facet, facet.len()) {
+                if !subset.is_empty() {
+                    simplices.insert(subset);
+                }
+            }
+        }
+        let simplices: Vec<Simplex> = simplices.into_iter().map(Simplex::new).collect();
+        let n = simplices.len();
+        SimplicialComplex::new(simplices, vec![0.0; n]).unwrap()
+    }
+
+    #[test]
+    fn test_smith_normal_form() {
+        let matrix = array![[2, 4, 4], [-6, 6, 12], [10, -4, -16]];
+        let snf = smith_normal_form(&matrix);
+        assert_eq!(snf.diagonal, vec![2, 6, 12]);
+
+        let diagonal = snf.left.dot(&matrix).dot(&snf.right);
+        assert_eq!(diagonal, Array2::from_diag(&array![2, 6, 12]));
+        assert_eq!(snf.left.dot(&snf.left_inverse), Array2::eye(3));
+        assert_eq!(snf.right.dot(&snf.right_inverse), Array2::eye(3));
+    }
+
+    #[test]
+    fn test_smith_normal_form_rectangular() {
+        let matrix = array![[1, -1, 0], [0, 1, -1]];
+        let snf = smith_normal_form(&matrix);
+        assert_eq!(snf.diagonal, vec![1, 1]);
+        assert_eq!(snf.rank(), 2);
+        assert_eq!(snf.left.dot(&matrix).dot(&snf.right), array![[1, 0, 0], [0, 1, 0]]);
+    }
+
+    #[test]
+    fn test_sphere() {
+        let complex = complex_from_facets(&[vec![0, 1, 2], vec![0, 1, 3], vec![0, 2, 3], vec![1, 2, 3]]);
+        let homology = complex.integral_homology();
+        assert_eq!((homology[&0].rank, homology[&1].rank, homology[&2].rank), (1, 0, 1));
+        assert!(homology.values().all(|h| h.torsion.is_empty()));
+
+        // The fundamental class uses every triangle with coefficient +-1
+        assert_eq!(homology[&2].generators[0].len(), 4);
+        assert!(homology[&2].generators[0].values().all(|c| c.abs() == 1));
+    }
+
+    #[test]
+    fn test_projective_plane_torsion() {
+        let complex = complex_from_facets(&[
+            vec![0, 1, 2],
+            vec![0, 2, 3],
+            vec![0, 3, 4],
+            vec![0, 4, 5],
+            vec![0, 1, 5],
+            vec![1, 2, 4],
+            vec![2, 3, 5],
+            vec![1, 3, 4],
+            vec![2, 4, 5],
+            vec![1, 3, 5],
+        ]);
+        let homology = complex.integral_homology();
+        assert_eq!(homology[&0].rank, 1);
+        assert_eq!(homology[&1].rank, 0);
+        assert_eq!(homology[&1].torsion, vec![2]);
+        assert_eq!(homology[&2], HomologyGroup::default());
+
+        // The torsion generator is a cycle
+        let mut boundary: HashMap<usize, i64> = HashMap::new();
+        for (&ix, &c) in &homology[&1].torsion_generators[0] {
+            for (face, sign) in complex.signed_boundary(ix) {
+                *boundary.entry(face).or_default() += c * sign;
+            }
+        }
+        assert!(boundary.values().all(|&c| c == 0));
+    }
+
+    #[test]
+    fn test_truncated_complex() {
+        let simplices = vec![
+            Simplex::new(vec![0]),
+            Simplex::new(vec![1]),
+            Simplex::new(vec![2]),
+            Simplex::new(vec![0, 1]),
+            Simplex::new(vec![1, 2]),
+            Simplex::new(vec![0, 2]),
+            Simplex::new(vec![0, 1, 2]),
+        ];
+        let levels = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.0];
+        let complex = SimplicialComplex::new(simplices, levels).unwrap();
+
+        assert_eq!(complex.truncate(0.5).integral_homology()[&0].rank, 3);
+        assert_eq!(complex.truncate(1.5).integral_homology()[&1].rank, 1);
+        assert_eq!(complex.integral_homology()[&1].rank, 0);
+    }
+}
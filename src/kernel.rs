@@ -0,0 +1,187 @@
+use ndarray::Array2;
+use ordered_float::OrderedFloat;
+use rayon::prelude::*;
+use std::f64::consts::PI;
+
+use super::homology::PersistenceInterval;
+
+/// Kernels between persistence diagrams.
+///
+/// Only finite intervals contribute, so infinite intervals should be truncated first (see
+/// [`PersistenceDiagram::truncate_infinite`](super::diagram::PersistenceDiagram::truncate_infinite))
+/// if they are to be taken into account.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kernel {
+    /// Persistence scale-space kernel (Reininghaus et al.), the heat kernel on the half-plane
+    /// above the diagonal, with scale σ
+    ScaleSpace { sigma: f64 },
+    /// Persistence weighted Gaussian kernel (Kusano et al.), a Gaussian kernel of width σ
+    /// between points weighted by `arctan(c * persistence^p)`
+    PersistenceWeightedGaussian { sigma: f64, c: f64, p: f64 },
+    /// Sliced Wasserstein kernel (Carrière et al.), a Gaussian of width σ of the sliced
+    /// Wasserstein distance, approximated with the given number of directions (at least one
+    /// direction is always used)
+    SlicedWasserstein { sigma: f64, n_directions: usize },
+}
+
+/// Returns the finite intervals as (birth, death) points.
+fn finite_points(intervals: &[PersistenceInterval]) -> Vec<(f64, f64)> {
+    intervals.iter().filter(|i| i.death.is_finite()).map(|i| (i.birth, i.death)).collect()
+}
+
+/// Returns the squared Euclidean distance between two points.
+fn squared_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)
+}
+
+/// Approximates the sliced Wasserstein distance between two sets of points, averaging the
+/// 1-Wasserstein distance between their projections over evenly spaced directions (at least
+/// one, so that the average is defined).
+fn sliced_wasserstein_distance(a: &[(f64, f64)], b: &[(f64, f64)], n_directions: usize) -> f64 {
+    let n_directions = n_directions.max(1);
+    // Each set is completed with the diagonal projections of the other, so that both have the
+    // same number of points
+    let diagonal = |&(birth, death): &(f64, f64)| ((birth + death) / 2.0, (birth + death) / 2.0);
+    let a_full: Vec<(f64, f64)> = a.iter().copied().chain(b.iter().map(diagonal)).collect();
+    let b_full: Vec<(f64, f64)> = b.iter().copied().chain(a.iter().map(diagonal)).collect();
+
+    let total: f64 = (0..n_directions)
+        .map(|ix| {
+            let theta = -PI / 2.0 + PI * ix as f64 / n_directions as f64;
+            let project = |points: &[(f64, f64)]| -> Vec<OrderedFloat<f64>> {
+                let mut projections: Vec<OrderedFloat<f64>> =
+                    points.iter().map(|&(x, y)| OrderedFloat(x * theta.cos() + y * theta.sin())).collect();
+                projections.sort();
+                projections
+            };
+            project(&a_full).iter().zip(project(&b_full)).map(|(p, q)| (p.0 - q.0).abs()).sum::<f64>()
+        })
+        .sum();
+    total / n_directions as f64
+}
+
+impl Kernel {
+    /// Evaluates the kernel between two sets of persistence intervals.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - First set of intervals (typically a single dimension of a diagram)
+    /// * `b` - Second set of intervals
+    pub fn evaluate(&self, a: &[PersistenceInterval], b: &[PersistenceInterval]) -> f64 {
+        let (a, b) = (finite_points(a), finite_points(b));
+        match *self {
+            Kernel::ScaleSpace { sigma } => {
+                let sum: f64 = a
+                    .iter()
+                    .flat_map(|&p| b.iter().map(move |&q| (p, q)))
+                    .map(|(p, q)| {
+                        // Each point of b has a negative copy mirrored about the diagonal
+                        let mirrored = (q.1, q.0);
+                        (-squared_distance(p, q) / (8.0 * sigma)).exp()
+                            - (-squared_distance(p, mirrored) / (8.0 * sigma)).exp()
+                    })
+                    .sum();
+                sum / (8.0 * PI * sigma)
+            },
+            Kernel::PersistenceWeightedGaussian { sigma, c, p } => {
+                let weight = |&(birth, death): &(f64, f64)| (c * (death - birth).powf(p)).atan();
+                a.iter()
+                    .flat_map(|x| b.iter().map(move |y| (x, y)))
+                    .map(|(x, y)| weight(x) * weight(y) * (-squared_distance(*x, *y) / (2.0 * sigma * sigma)).exp())
+                    .sum()
+            },
+            Kernel::SlicedWasserstein { sigma, n_directions } => {
+                (-sliced_wasserstein_distance(&a, &b, n_directions) / (2.0 * sigma * sigma)).exp()
+            },
+        }
+    }
+}
+
+/// Computes the Gram matrix of a kernel over a list of persistence diagrams, in parallel.
+///
+/// Kernels are symmetric, so each pair of diagrams is evaluated once: the entries on and above
+/// the diagonal are computed in parallel and mirrored below it.
+///
+/// # Arguments
+///
+/// * `kernel` - Kernel to evaluate
+/// * `diagrams` - Sets of intervals (typically a single dimension of each diagram)
+///
+/// # Returns
+///
+/// A symmetric matrix of kernel values between each pair of diagrams
+pub fn gram_matrix<D: AsRef<[PersistenceInterval]> + Sync>(kernel: &Kernel, diagrams: &[D]) -> Array2<f64> {
+    let n = diagrams.len();
+    let entries: Vec<(usize, usize, f64)> = (0..n)
+        .into_par_iter()
+        .flat_map_iter(|i| (i..n).map(move |j| (i, j, kernel.evaluate(diagrams[i].as_ref(), diagrams[j].as_ref()))))
+        .collect();
+
+    let mut gram = Array2::zeros((n, n));
+    for (i, j, value) in entries {
+        gram[[i, j]] = value;
+        gram[[j, i]] = value;
+    }
+    gram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::intervals;
+
+    fn kernels() -> Vec<Kernel> {
+        vec![
+            Kernel::ScaleSpace { sigma: 0.5 },
+            Kernel::PersistenceWeightedGaussian { sigma: 0.5, c: 1.0, p: 1.0 },
+            Kernel::SlicedWasserstein { sigma: 1.0, n_directions: 10 },
+        ]
+    }
+
+    #[test]
+    fn test_single_points() {
+        let a = intervals(&[(0.0, 1.0)]);
+        let b = intervals(&[(0.0, 2.0), (1.0, f64::INFINITY)]);
+
+        let expected = ((-1.0 / 4.0_f64).exp() - (-5.0 / 4.0_f64).exp()) / (4.0 * PI);
+        assert!((Kernel::ScaleSpace { sigma: 0.5 }.evaluate(&a, &b) - expected).abs() < 1e-12);
+
+        let expected = 1.0_f64.atan() * 2.0_f64.atan() * (-2.0_f64).exp();
+        let kernel = Kernel::PersistenceWeightedGaussian { sigma: 0.5, c: 1.0, p: 1.0 };
+        assert!((kernel.evaluate(&a, &b) - expected).abs() < 1e-12);
+
+        // Points on the diagonal do not contribute
+        let kernel = Kernel::SlicedWasserstein { sigma: 1.0, n_directions: 10 };
+        assert_eq!(kernel.evaluate(&a, &a), 1.0);
+        assert_eq!(kernel.evaluate(&intervals(&[(1.0, 1.0)]), &[]), 1.0);
+        assert!(kernel.evaluate(&a, &b) < 1.0);
+
+        // Without directions, a single direction is used instead of averaging over none
+        let no_directions = Kernel::SlicedWasserstein { sigma: 1.0, n_directions: 0 };
+        let one_direction = Kernel::SlicedWasserstein { sigma: 1.0, n_directions: 1 };
+        assert_eq!(no_directions.evaluate(&a, &b), one_direction.evaluate(&a, &b));
+        assert!(gram_matrix(&no_directions, &[a.clone(), b.clone()]).iter().all(|value| value.is_finite()));
+    }
+
+    #[test]
+    fn test_gram_matrix() {
+        let diagrams = vec![
+            intervals(&[(0.0, 1.0), (0.5, 2.0)]),
+            intervals(&[(0.0, 1.5)]),
+            intervals(&[]),
+            intervals(&[(1.0, 3.0), (0.0, 0.1), (2.0, 2.5)]),
+        ];
+        for kernel in kernels() {
+            let gram = gram_matrix(&kernel, &diagrams);
+            assert_eq!(gram.dim(), (4, 4));
+            for i in 0..4 {
+                for j in 0..4 {
+                    assert_eq!(gram[[i, j]], gram[[j, i]]);
+                    assert!((gram[[i, j]] - kernel.evaluate(&diagrams[i], &diagrams[j])).abs() < 1e-12);
+                }
+                // Positive semi-definite kernels have non-negative diagonals
+                assert!(gram[[i, i]] >= 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use ndarray::Array2;
+use ordered_float::OrderedFloat;
+
+use super::homology::PersistenceInterval;
+
+/// A piecewise-linear function given by its breakpoints, sorted by x and zero outside of them.
+type PiecewiseLinear = Vec<(f64, f64)>;
+
+/// Evaluates a piecewise-linear function, which is zero outside of its breakpoints.
+fn evaluate(function: &[(f64, f64)], x: f64) -> f64 {
+    let ix = function.partition_point(|&(px, _)| px <= x);
+    if ix == 0 || ix == function.len() {
+        // Outside the support, or exactly at the last breakpoint
+        return if ix > 0 && function[ix - 1].0 == x { function[ix - 1].1 } else { 0.0 };
+    }
+    let ((x0, y0), (x1, y1)) = (function[ix - 1], function[ix]);
+    y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+}
+
+/// Combines two piecewise-linear functions pointwise with a linear operation, exactly, on the
+/// union of their breakpoints.
+fn combine(f: &[(f64, f64)], g: &[(f64, f64)], op: impl Fn(f64, f64) -> f64) -> PiecewiseLinear {
+    let mut xs: Vec<OrderedFloat<f64>> = f.iter().chain(g).map(|&(x, _)| OrderedFloat(x)).collect();
+    xs.sort();
+    xs.dedup();
+    xs.into_iter().map(|x| (x.into_inner(), op(evaluate(f, x.into_inner()), evaluate(g, x.into_inner())))).collect()
+}
+
+/// Returns the exact integral of |f|^p for a piecewise-linear function f.
+fn integral_of_power(function: &[(f64, f64)], p: f64) -> f64 {
+    // Integral of |y|^p over a segment where y is linear from y0 to y1 and does not change sign
+    let segment = |width: f64, y0: f64, y1: f64| {
+        let (a, b) = (y0.abs(), y1.abs());
+        if (a - b).abs() <= f64::EPSILON * a.max(b) {
+            width * a.powf(p)
+        } else {
+            width * (b.powf(p + 1.0) - a.powf(p + 1.0)) / ((p + 1.0) * (b - a))
+        }
+    };
+
+    function
+        .windows(2)
+        .map(|w| {
+            let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+            if y0 * y1 < 0.0 {
+                // Split at the root
+                let root = x0 + (x1 - x0) * y0 / (y0 - y1);
+                segment(root - x0, y0, 0.0) + segment(x1 - root, 0.0, y1)
+            } else {
+                segment(x1 - x0, y0, y1)
+            }
+        })
+        .sum()
+}
+
+/// Persistence landscape of a set of persistence intervals, stored exactly.
+///
+/// The k-th landscape function λ_k maps x to the k-th largest value of
+/// `max(0, min(x - birth, death - x))` over the intervals. Each function is piecewise linear and
+/// is stored as its breakpoints, computed with the algorithm of Bubenik and Dłotko.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PersistenceLandscape {
+    /// Breakpoints of each landscape function, λ_1 first
+    functions: Vec<PiecewiseLinear>,
+}
+
+impl PersistenceLandscape {
+    /// Computes the persistence landscape of a set of intervals.
+    ///
+    /// Infinite intervals are ignored, so they should be truncated first (see
+    /// [`PersistenceDiagram::truncate_infinite`](super::diagram::PersistenceDiagram::truncate_infinite))
+    /// if they are to contribute.
+    ///
+    /// # Arguments
+    ///
+    /// * `intervals` - Intervals of a single dimension, e.g. from
+    ///   [`ChainComplex::persistence_intervals`](super::homology::ChainComplex::persistence_intervals)
+    ///
+    /// # Returns
+    ///
+    /// A new PersistenceLandscape instance
+    pub fn new(intervals: &[PersistenceInterval]) -> Self {
+        // Sorted by increasing birth, then decreasing death
+        let mut remaining: Vec<(f64, f64)> =
+            intervals.iter().filter(|i| i.death.is_finite() && i.death > i.birth).map(|i| (i.birth, i.death)).collect();
+        let key = |&(birth, death): &(f64, f64)| (OrderedFloat(birth), OrderedFloat(-death));
+        remaining.sort_by_key(key);
+
+        let mut functions = Vec::new();
+        while !remaining.is_empty() {
+            let (birth, mut death) = remaining.remove(0);
+            let mut function = vec![(birth, 0.0), ((birth + death) / 2.0, (death - birth) / 2.0)];
+
+            // Intervals before this position die no later than the current one
+            let mut position = 0;
+            loop {
+                let Some(ix) = (position..remaining.len()).find(|&ix| remaining[ix].1 > death) else {
+                    function.push((death, 0.0));
+                    break;
+                };
+                let (next_birth, next_death) = remaining.remove(ix);
+                if next_birth > death {
+                    function.push((death, 0.0));
+                }
+                if next_birth >= death {
+                    function.push((next_birth, 0.0));
+                } else {
+                    // The functions cross, the overlap goes to the next landscape
+                    function.push(((next_birth + death) / 2.0, (death - next_birth) / 2.0));
+                    let overlap = (next_birth, death);
+                    let insert_at = ix + remaining[ix..].partition_point(|i| key(i) < key(&overlap));
+                    remaining.insert(insert_at, overlap);
+                }
+                function.push(((next_birth + next_death) / 2.0, (next_death - next_birth) / 2.0));
+                death = next_death;
+                position = ix;
+            }
+            functions.push(function);
+        }
+
+        Self { functions }
+    }
+
+    /// Returns the number of landscape functions which are not identically zero.
+    pub fn depth(&self) -> usize {
+        self.functions.len()
+    }
+
+    /// Returns the breakpoints of the k-th landscape function (k = 0 for λ_1), between which
+    /// the function is linear. The function is zero outside of them.
+    pub fn breakpoints(&self, k: usize) -> &[(f64, f64)] {
+        self.functions.get(k).map_or(&[], |f| f.as_slice())
+    }
+
+    /// Evaluates the k-th landscape function (k = 0 for λ_1) at x.
+    pub fn evaluate(&self, k: usize, x: f64) -> f64 {
+        evaluate(self.breakpoints(k), x)
+    }
+
+    /// Samples the first landscape functions on a grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_landscapes` - Number of landscape functions to sample
+    /// * `grid` - Values at which to evaluate the functions
+    ///
+    /// # Returns
+    ///
+    /// An array with one row per landscape function and one column per grid value.
+    pub fn sample(&self, n_landscapes: usize, grid: &[f64]) -> Array2<f64> {
+        Array2::from_shape_fn((n_landscapes, grid.len()), |(k, ix)| self.evaluate(k, grid[ix]))
+    }
+
+    /// Computes the average of several landscapes, exactly.
+    ///
+    /// # Arguments
+    ///
+    /// * `landscapes` - Landscapes to average, e.g. of different subsamples
+    ///
+    /// # Returns
+    ///
+    /// The pointwise mean landscape (empty if there are no landscapes).
+    pub fn average(landscapes: &[PersistenceLandscape]) -> Self {
+        let depth = landscapes.iter().map(|l| l.depth()).max().unwrap_or(0);
+        let scale = 1.0 / landscapes.len() as f64;
+        let functions = (0..depth)
+            .map(|k| {
+                landscapes
+                    .iter()
+                    .fold(Vec::new(), |sum, landscape| combine(&sum, landscape.breakpoints(k), |a, b| a + scale * b))
+            })
+            .collect();
+        Self { functions }
+    }
+
+    /// Returns the L^p norm of the landscape, i.e. of the sequence of its functions.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Exponent, at least 1 (infinity for the supremum norm)
+    pub fn norm(&self, p: f64) -> f64 {
+        norm(&self.functions, p)
+    }
+
+    /// Returns the L^p distance between two landscapes, i.e. the norm of their difference.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Landscape to compare with
+    /// * `p` - Exponent, at least 1 (infinity for the supremum norm)
+    pub fn distance(&self, other: &PersistenceLandscape, p: f64) -> f64 {
+        let depth = self.depth().max(other.depth());
+        let differences: Vec<PiecewiseLinear> =
+            (0..depth).map(|k| combine(self.breakpoints(k), other.breakpoints(k), |a, b| a - b)).collect();
+        norm(&differences, p)
+    }
+}
+
+/// Returns the L^p norm of a sequence of piecewise-linear functions.
+fn norm(functions: &[PiecewiseLinear], p: f64) -> f64 {
+    if p.is_infinite() {
+        functions.iter().flatten().map(|&(_, y)| y.abs()).fold(0.0, f64::max)
+    } else {
+        functions.iter().map(|f| integral_of_power(f, p)).sum::<f64>().powf(1.0 / p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::intervals;
+
+    /// Evaluates λ_k from its definition.
+    fn brute_force(pairs: &[(f64, f64)], k: usize, x: f64) -> f64 {
+        let mut values: Vec<f64> = pairs.iter().map(|&(b, d)| (x - b).min(d - x).max(0.0)).collect();
+        values.sort_by(|a, b| b.total_cmp(a));
+        values.get(k).copied().unwrap_or(0.0)
+    }
+
+    #[test]
+    fn test_overlapping_intervals() {
+        let landscape = PersistenceLandscape::new(&intervals(&[(0.0, 2.0), (1.0, 3.0), (5.0, f64::INFINITY)]));
+        assert_eq!(landscape.depth(), 2);
+        assert_eq!(landscape.breakpoints(0), &[(0.0, 0.0), (1.0, 1.0), (1.5, 0.5), (2.0, 1.0), (3.0, 0.0)]);
+        assert_eq!(landscape.breakpoints(1), &[(1.0, 0.0), (1.5, 0.5), (2.0, 0.0)]);
+        assert!(landscape.breakpoints(2).is_empty());
+        assert_eq!(landscape.sample(3, &[0.5, 1.5, 4.0]), ndarray::array![[0.5, 0.5, 0.0], [0.0, 0.5, 0.0], [0.0; 3]]);
+    }
+
+    #[test]
+    fn test_against_definition() {
+        let pairs = [(0.0, 4.0), (1.0, 3.0), (0.5, 2.0), (2.5, 6.0), (7.0, 8.0), (1.0, 5.0), (2.0, 2.5)];
+        let landscape = PersistenceLandscape::new(&intervals(&pairs));
+        for k in 0..pairs.len() + 1 {
+            for step in 0..100 {
+                let x = -0.5 + 0.09 * step as f64;
+                assert!((landscape.evaluate(k, x) - brute_force(&pairs, k, x)).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_norms_and_distances() {
+        let tent = PersistenceLandscape::new(&intervals(&[(0.0, 2.0)]));
+        assert!((tent.norm(1.0) - 1.0).abs() < 1e-12);
+        assert!((tent.norm(2.0) - (2.0_f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(tent.norm(f64::INFINITY), 1.0);
+
+        let shifted = PersistenceLandscape::new(&intervals(&[(1.0, 3.0)]));
+        assert_eq!(tent.distance(&tent, 2.0), 0.0);
+        assert_eq!(tent.distance(&shifted, f64::INFINITY), 1.0);
+        // |difference| consists of two triangles of area 1/2 and two of area 1/4
+        assert!((tent.distance(&shifted, 1.0) - 1.5).abs() < 1e-12);
+        assert!((tent.distance(&shifted, 2.0) - shifted.distance(&tent, 2.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_average() {
+        let a = PersistenceLandscape::new(&intervals(&[(0.0, 2.0), (1.0, 3.0)]));
+        let b = PersistenceLandscape::new(&intervals(&[(0.5, 4.0)]));
+        let average = PersistenceLandscape::average(&[a.clone(), b.clone()]);
+        assert_eq!(average.depth(), 2);
+        for step in 0..50 {
+            let x = 0.1 * step as f64;
+            for k in 0..2 {
+                let expected = (a.evaluate(k, x) + b.evaluate(k, x)) / 2.0;
+                assert!((average.evaluate(k, x) - expected).abs() < 1e-12);
+            }
+        }
+        assert_eq!(PersistenceLandscape::average(&[]).depth(), 0);
+    }
+}
@@ -1,10 +1,29 @@
+pub mod boundary_matrix;
+pub mod circular;
+pub mod cubical;
+pub mod cycles;
 pub mod dbscan;
+pub mod diagram;
+pub mod distance;
+pub mod field;
 pub mod homology;
+pub mod image;
+pub mod integral;
+pub mod kernel;
+pub mod landscape;
 pub mod mapper;
 pub mod point_cloud;
+pub mod simplex_tree;
 pub mod simplicial_complex;
+pub mod sparse;
+pub mod stats;
 
 mod combinatorics;
+mod reduction;
+#[cfg(test)]
+mod test_utils;
+
+pub use combinatorics::{BinomialTable, CombinatoricsError};
 
 // Python packaging
 #[cfg(feature = "python")]
@@ -1,4 +1,7 @@
+use super::circular::{circular_coordinates, CircularCoordinatesError};
 use super::combinatorics::generate_subsets;
+use super::field::PrimeField;
+use super::homology::{ChainComplex, PersistenceAlgorithm, PersistenceInterval, PersistenceOptions};
 use super::simplicial_complex::{Simplex, SimplicialComplex};
 use ndarray::{Array2, ArrayView1};
 use ordered_float::OrderedFloat;
@@ -185,12 +188,44 @@ impl PointCloud {
 
         SimplicialComplex::new(simplices, filtration).map_err(|e| PointCloudError::InvalidDimension(e.to_string()))
     }
+
+    /// Compute circular coordinates of the points from the most persistent 1-dimensional
+    /// cohomology class of the Vietoris-Rips filtration.
+    ///
+    /// The cohomology is computed over Z/p, and the coordinates in the middle of the interval
+    /// (capped at the threshold). See [`circular_coordinates`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Distance threshold for the Vietoris-Rips complex
+    /// * `prime` - Prime p of the coefficients, which should not divide any torsion (e.g. 47)
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the coordinate in [0, 1) of each point or an error if:
+    /// - The prime is invalid
+    /// - The point cloud has no 1-dimensional feature below the threshold
+    /// - The cocycle does not lift to an integer cocycle
+    pub fn circular_coordinates(&self, threshold: f64, prime: u64) -> Result<Vec<f64>, CircularCoordinatesError> {
+        let field = PrimeField::new(prime)?;
+        let complex = self.vietoris_rips_complex(2, threshold)?;
+        let options = PersistenceOptions { algorithm: PersistenceAlgorithm::Cohomology, field, ..Default::default() };
+        let intervals = complex.persistence_intervals_with_options(&options);
+
+        let lifetime = |i: &PersistenceInterval| i.death.min(threshold) - i.birth;
+        let interval = intervals
+            .get(&1)
+            .and_then(|ints| ints.iter().filter(|i| lifetime(i) > 0.0).max_by_key(|i| OrderedFloat(lifetime(i))))
+            .ok_or(CircularCoordinatesError::NoInterval)?;
+        let level = interval.birth + lifetime(interval) / 2.0;
+
+        circular_coordinates(&complex, interval, &field, level)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::homology::ChainComplex;
     use log::debug;
     use ndarray::array;
     use std::collections::{HashMap, HashSet};
@@ -222,7 +257,7 @@ mod tests {
         let point_cloud = PointCloud::new(array![[0.0, 0.0], [1.0, 0.0], [1.0, 2.0],]).unwrap();
 
         let dist_matrix = point_cloud.pairwise_distances();
-        let sqrt5 = (5.0 as f64).sqrt();
+        let sqrt5 = 5.0_f64.sqrt();
         let expected = array![[0.0, 1.0, sqrt5], [1.0, 0.0, 2.0], [sqrt5, 2.0, 0.0],];
         assert_eq!(dist_matrix, expected);
 
@@ -9,7 +9,8 @@ use pyo3::types::{PyDict, PyList, PyTuple};
 
 use super::dbscan::dbscan as dbscan_rs;
 use super::dbscan::DBSCANError;
-use super::homology::ChainComplex;
+use super::homology::{ChainComplex, PersistenceOptions, Representatives};
+use super::image::{persistence_image as persistence_image_rs, PersistenceImageOptions};
 use super::mapper::mapper as mapper_rs;
 use super::mapper::{MapperError, Node};
 use super::point_cloud::{PointCloud, PointCloudError};
@@ -25,6 +26,7 @@ use std::collections::HashMap;
 pub fn persistence(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(pairwise_distances, m)?)?;
     m.add_function(wrap_pyfunction!(persistence_intervals, m)?)?;
+    m.add_function(wrap_pyfunction!(persistence_image, m)?)?;
     m.add_function(wrap_pyfunction!(dbscan, m)?)?;
     m.add_function(wrap_pyfunction!(mapper, m)?)?;
     Ok(())
@@ -68,11 +70,15 @@ pub fn pairwise_distances(py: Python, points: PyReadonlyArray2<f64>) -> PyResult
 /// * `points` - 2D numpy array where each row is a point and each column is a dimension
 /// * `max_dimension` - Maximum homology dimension to compute
 /// * `threshold` - Distance threshold for the Vietoris-Rips complex
+/// * `representatives` - Whether to compute representative chains (default true)
+/// * `representative_threshold` - If given, only compute representatives for intervals with
+///   persistence greater than this value
 ///
 /// # Returns
 ///
 /// A dictionary mapping dimensions to lists of persistence intervals.
-/// Each interval is a tuple (birth, birth_chain, death, death_chain).
+/// Each interval is a tuple (birth, birth_chain, death, death_chain), where the chains are
+/// empty (or None) when representatives were not computed.
 ///
 /// # Raises
 ///
@@ -82,11 +88,14 @@ pub fn pairwise_distances(py: Python, points: PyReadonlyArray2<f64>) -> PyResult
 ///   - The threshold is negative
 #[cfg(feature = "python")]
 #[pyfunction]
+#[pyo3(signature = (points, max_dimension, threshold, representatives=true, representative_threshold=None))]
 pub fn persistence_intervals(
     py: Python,
     points: PyReadonlyArray2<f64>,
     max_dimension: usize,
     threshold: f64,
+    representatives: bool,
+    representative_threshold: Option<f64>,
 ) -> PyResult<Py<PyDict>> {
     // Validate inputs
     if threshold < 0.0 {
@@ -106,7 +115,13 @@ pub fn persistence_intervals(
         .vietoris_rips_complex(max_dimension, threshold)
         .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
-    let intervals = complex.persistence_intervals();
+    let representatives = match (representatives, representative_threshold) {
+        (false, _) => Representatives::None,
+        (true, None) => Representatives::All,
+        (true, Some(value)) => Representatives::AboveThreshold(value),
+    };
+    let options = PersistenceOptions { representatives, ..Default::default() };
+    let intervals = complex.persistence_intervals_with_options(&options);
 
     // Convert to Python objects
     let py_intervals = PyDict::new(py);
@@ -131,6 +146,76 @@ pub fn persistence_intervals(
     Ok(py_intervals.into())
 }
 
+/// Compute the persistence image of a point cloud in a given dimension.
+///
+/// This function constructs a Vietoris-Rips complex from the input points, computes its
+/// persistent homology (without representatives) and vectorizes the intervals of the given
+/// dimension with linear weighting.
+///
+/// # Arguments
+///
+/// * `points` - 2D numpy array where each row is a point and each column is a dimension
+/// * `dimension` - Homology dimension to vectorize
+/// * `threshold` - Distance threshold for the Vietoris-Rips complex
+/// * `resolution` - Number of pixels along the birth and persistence axes
+/// * `bandwidth` - Standard deviation of the Gaussian placed on each point
+/// * `birth_range` - Range of births covered by the image (the range of the diagram if None)
+/// * `persistence_range` - Range of persistence covered by the image (up to the largest
+///   persistence if None)
+///
+/// # Returns
+///
+/// A 2D numpy array with one row per persistence pixel and one column per birth pixel.
+///
+/// # Raises
+///
+/// * `ValueError` if:
+///   - The input array is empty or has invalid dimensions
+///   - The threshold is negative or the bandwidth is not positive
+#[cfg(feature = "python")]
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (points, dimension, threshold, resolution=(20, 20), bandwidth=0.1, birth_range=None, persistence_range=None))]
+pub fn persistence_image(
+    py: Python,
+    points: PyReadonlyArray2<f64>,
+    dimension: usize,
+    threshold: f64,
+    resolution: (usize, usize),
+    bandwidth: f64,
+    birth_range: Option<(f64, f64)>,
+    persistence_range: Option<(f64, f64)>,
+) -> PyResult<Py<PyArray2<f64>>> {
+    // Validate inputs
+    if threshold < 0.0 {
+        return Err(PyValueError::new_err("Threshold must be non-negative"));
+    }
+    if bandwidth <= 0.0 {
+        return Err(PyValueError::new_err("Bandwidth must be positive"));
+    }
+
+    let points: Array2<f64> = points.as_array().into_owned();
+    let point_cloud = PointCloud::new(points).map_err(|e| match e {
+        PointCloudError::EmptyCloud => PyValueError::new_err("Empty point cloud"),
+        PointCloudError::DimensionMismatch { expected, got } => {
+            PyValueError::new_err(format!("Inconsistent point dimensions: expected {}, got {}", expected, got))
+        },
+        _ => PyRuntimeError::new_err(e.to_string()),
+    })?;
+
+    let complex = point_cloud
+        .vietoris_rips_complex(dimension + 1, threshold)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    let options = PersistenceOptions { representatives: Representatives::None, ..Default::default() };
+    let intervals = complex.persistence_intervals_with_options(&options);
+    let image_options =
+        PersistenceImageOptions { resolution, bandwidth, birth_range, persistence_range, ..Default::default() };
+    let image = persistence_image_rs(intervals.get(&dimension).map_or(&[], |ints| ints.as_slice()), &image_options);
+
+    Ok(image.to_pyarray(py).into())
+}
+
 /// Clusters given points via DBSCAN algorithm.
 ///
 /// # Arguments
@@ -199,7 +284,7 @@ pub fn mapper(
     let py_graph = PyDict::new(py);
     for (node, neighbours) in result.adjacency_list {
         let key = nodes[&node];
-        let value: &PyList = PyList::new(py, neighbours.iter().map(|n| nodes[&n]).collect::<Vec<usize>>());
+        let value: &PyList = PyList::new(py, neighbours.iter().map(|n| nodes[n]).collect::<Vec<usize>>());
         py_graph.set_item(key, value)?;
     }
 
@@ -0,0 +1,588 @@
+use std::collections::{HashMap, HashSet};
+
+use super::field::Field;
+use super::homology::{PersistenceOptions, Representatives};
+use log::debug;
+
+/// A sparse column of a boundary matrix over a finite field.
+///
+/// The column is stored as a vector of (row index, coefficient) entries sorted by row index and
+/// holding only non-zero coefficients, so the pivot (lowest non-zero row) is simply the last
+/// entry and column additions are linear merges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct SparseColumn {
+    /// Row indices and coefficients of the non-zero entries, in ascending order of row
+    entries: Vec<(usize, u64)>,
+}
+
+impl SparseColumn {
+    /// Creates a column with a single unit entry.
+    pub fn unit(index: usize) -> Self {
+        Self { entries: vec![(index, 1)] }
+    }
+
+    /// Creates a column from integer entries, reducing them into the given field.
+    ///
+    /// Entries may be unsorted and contain repeated rows, whose coefficients are summed.
+    pub fn from_entries<F: Field>(mut entries: Vec<(usize, i64)>, field: &F) -> Self {
+        entries.sort_unstable_by_key(|&(ix, _)| ix);
+        let mut column: Vec<(usize, u64)> = Vec::with_capacity(entries.len());
+        for (ix, coefficient) in entries {
+            let coefficient = field.element(coefficient);
+            match column.last_mut() {
+                Some((last, value)) if *last == ix => *value = field.add(*value, coefficient),
+                _ => column.push((ix, coefficient)),
+            }
+        }
+        column.retain(|&(_, value)| value != 0);
+        Self { entries: column }
+    }
+
+    /// Returns the pivot (largest row index with a non-zero entry), if any.
+    pub fn pivot(&self) -> Option<usize> {
+        self.entries.last().map(|&(ix, _)| ix)
+    }
+
+    /// Returns the pivot and its coefficient, if any.
+    pub fn pivot_entry(&self) -> Option<(usize, u64)> {
+        self.entries.last().copied()
+    }
+
+    /// Returns true if the column has no non-zero entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the row indices of the non-zero entries in ascending order.
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.entries.iter().map(|&(ix, _)| ix)
+    }
+
+    /// Iterates over the (row index, coefficient) entries in ascending order of row.
+    pub fn entries(&self) -> impl Iterator<Item = &(usize, u64)> {
+        self.entries.iter()
+    }
+
+    /// Maps each row index `ix` to `n - 1 - ix`, i.e. reverses the order of the rows.
+    pub fn reversed(&self, n: usize) -> Self {
+        Self { entries: self.entries.iter().rev().map(|&(ix, value)| (n - 1 - ix, value)).collect() }
+    }
+
+    /// Adds a multiple of another column to this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Column to add
+    /// * `factor` - Coefficient by which `other` is multiplied
+    /// * `field` - Field of coefficients
+    pub fn add_scaled<F: Field>(&mut self, other: &SparseColumn, factor: u64, field: &F) {
+        let mut merged = Vec::with_capacity(self.entries.len() + other.entries.len());
+        let (mut a, mut b) = (self.entries.iter().peekable(), other.entries.iter().peekable());
+        loop {
+            match (a.peek(), b.peek()) {
+                (Some(&&(x, u)), Some(&&(y, _))) if x < y => {
+                    merged.push((x, u));
+                    a.next();
+                },
+                (Some(&&(x, _)), Some(&&(y, v))) if x > y => {
+                    merged.push((y, field.mul(factor, v)));
+                    b.next();
+                },
+                (Some(&&(x, u)), Some(&&(_, v))) => {
+                    // Equal rows combine, and cancel if the sum vanishes
+                    let value = field.add(u, field.mul(factor, v));
+                    if value != 0 {
+                        merged.push((x, value));
+                    }
+                    a.next();
+                    b.next();
+                },
+                (Some(&&(x, u)), None) => {
+                    merged.push((x, u));
+                    a.next();
+                },
+                (None, Some(&&(y, v))) => {
+                    merged.push((y, field.mul(factor, v)));
+                    b.next();
+                },
+                (None, None) => break,
+            }
+        }
+        self.entries = merged;
+    }
+}
+
+impl From<HashSet<usize>> for SparseColumn {
+    /// Creates a column with unit coefficients on the given rows.
+    fn from(set: HashSet<usize>) -> Self {
+        let mut entries: Vec<(usize, u64)> = set.into_iter().map(|ix| (ix, 1)).collect();
+        entries.sort_unstable();
+        Self { entries }
+    }
+}
+
+impl From<&SparseColumn> for HashSet<usize> {
+    /// Returns the rows with non-zero entries.
+    fn from(column: &SparseColumn) -> Self {
+        column.indices().collect()
+    }
+}
+
+impl From<&SparseColumn> for HashMap<usize, u64> {
+    /// Returns the coefficient of each row with a non-zero entry.
+    fn from(column: &SparseColumn) -> Self {
+        column.entries().copied().collect()
+    }
+}
+
+/// Result of reducing a matrix `D` to a column-reduced matrix `R = D V`.
+///
+/// Rather than storing `V`, the column operations performed on each column are recorded (when
+/// requested), so that columns of `V` can be reconstructed for just the chains that are needed.
+#[derive(Clone, Debug)]
+pub(crate) struct Reduction {
+    /// Columns of the reduced matrix `R`
+    pub reduced: Vec<SparseColumn>,
+    /// For each row, the column whose pivot lies in that row (if any)
+    pub pivot_column: Vec<Option<usize>>,
+    /// Whether each column was cleared, in which case its chain is the column that killed it
+    cleared: Vec<bool>,
+    /// Column operations, as (added column, factor), applied to each column (if tracked)
+    operations: Option<Vec<Vec<(usize, u64)>>>,
+}
+
+impl Reduction {
+    /// Reconstructs the columns of `V` (the chains whose boundaries are the reduced columns) for
+    /// the given columns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the column operations were not tracked during the reduction.
+    pub fn chains<F: Field>(&self, columns: &[usize], field: &F) -> Vec<SparseColumn> {
+        let operations = self.operations.as_ref().expect("Column operations were not tracked");
+        let n = self.reduced.len();
+
+        // Find every column whose chain contributes to the requested ones
+        let mut needed = vec![false; n];
+        let mut stack = columns.to_vec();
+        while let Some(jx) = stack.pop() {
+            if !needed[jx] {
+                needed[jx] = true;
+                if !self.cleared[jx] {
+                    stack.extend(operations[jx].iter().map(|&(kx, _)| kx));
+                }
+            }
+        }
+
+        // Operations only add earlier columns, so chains can be built in index order
+        let mut chains: Vec<Option<SparseColumn>> = vec![None; n];
+        for jx in (0..n).filter(|&jx| needed[jx]) {
+            let chain = if self.cleared[jx] {
+                self.reduced[self.pivot_column[jx].unwrap()].clone()
+            } else {
+                let mut chain = SparseColumn::unit(jx);
+                for &(kx, factor) in &operations[jx] {
+                    chain.add_scaled(chains[kx].as_ref().unwrap(), factor, field);
+                }
+                chain
+            };
+            chains[jx] = Some(chain);
+        }
+
+        columns.iter().map(|&jx| chains[jx].clone().unwrap()).collect()
+    }
+}
+
+/// A persistence pair read off a reduced matrix, in terms of column indices of the original
+/// (filtration-ordered) complex.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct PersistencePair {
+    /// Index of the chain creating the feature
+    pub birth: usize,
+    /// Index of the chain destroying the feature (None if it persists)
+    pub death: Option<usize>,
+}
+
+/// Persistence pairs computed by a matrix reduction, from which representatives can be obtained.
+#[derive(Clone, Debug)]
+pub(crate) struct PersistenceReduction {
+    /// Finite pairs in order of their death index, followed by the essential features in order
+    /// of their birth index
+    pub pairs: Vec<PersistencePair>,
+    /// Reduction of the boundary or coboundary matrix
+    reduction: Reduction,
+    /// Whether the coboundary matrix was reduced (with rows and columns in reverse order)
+    cohomology: bool,
+}
+
+impl PersistenceReduction {
+    /// Computes representatives of the given pairs, as (birth chain, death chain).
+    ///
+    /// For homology, the birth chain is the cycle created at birth and the death chain is the
+    /// chain whose boundary kills it. For cohomology, the birth chain is the cocycle created at
+    /// birth (valid until death) and the death chain is its coboundary, whose oldest element is
+    /// the chain at which the cocycle dies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the column operations were not tracked during the reduction.
+    pub fn representatives<F: Field>(
+        &self,
+        pairs: &[PersistencePair],
+        field: &F,
+    ) -> Vec<(SparseColumn, Option<SparseColumn>)> {
+        if self.cohomology {
+            let n = self.reduction.reduced.len();
+            let columns: Vec<usize> = pairs.iter().map(|pair| n - 1 - pair.birth).collect();
+            let chains = self.reduction.chains(&columns, field);
+            pairs
+                .iter()
+                .zip(columns)
+                .zip(chains)
+                .map(|((pair, column_ix), chain)| {
+                    (chain.reversed(n), pair.death.map(|_| self.reduction.reduced[column_ix].reversed(n)))
+                })
+                .collect()
+        } else {
+            let births: Vec<usize> = pairs.iter().map(|pair| pair.birth).collect();
+            let deaths: Vec<usize> = pairs.iter().filter_map(|pair| pair.death).collect();
+            let mut birth_chains = self.reduction.chains(&births, field).into_iter();
+            let mut death_chains = self.reduction.chains(&deaths, field).into_iter();
+            pairs
+                .iter()
+                .map(|pair| (birth_chains.next().unwrap(), pair.death.and_then(|_| death_chains.next())))
+                .collect()
+        }
+    }
+}
+
+/// Groups column indices by dimension, in ascending order of dimension.
+fn columns_by_dim(dims: &[usize]) -> Vec<Vec<usize>> {
+    let max_dim = dims.iter().copied().max().unwrap_or(0);
+    let mut groups: Vec<Vec<usize>> = vec![Vec::new(); max_dim + 1];
+    for (ix, &dim) in dims.iter().enumerate() {
+        groups[dim].push(ix);
+    }
+    groups
+}
+
+/// Finds the apparent pairs of a filtration from its boundary matrix.
+///
+/// A pair (σ, τ) is apparent if σ is the youngest facet of τ and τ is the oldest cofacet of σ.
+/// Apparent pairs are always persistence pairs, and the boundary column of τ is already reduced
+/// with pivot σ, so they can be emitted without any column additions. For Vietoris-Rips (and
+/// other flag) filtrations most persistence pairs are apparent.
+///
+/// # Arguments
+///
+/// * `boundaries` - Boundary columns, in filtration order
+///
+/// # Returns
+///
+/// The apparent pairs as (facet index, cofacet index).
+pub(crate) fn apparent_pairs(boundaries: &[SparseColumn]) -> Vec<(usize, usize)> {
+    let mut oldest_cofacet: Vec<Option<usize>> = vec![None; boundaries.len()];
+    for (jx, column) in boundaries.iter().enumerate().rev() {
+        for ix in column.indices() {
+            oldest_cofacet[ix] = Some(jx);
+        }
+    }
+
+    boundaries
+        .iter()
+        .enumerate()
+        .filter_map(|(jx, column)| column.pivot().map(|ix| (ix, jx)))
+        .filter(|&(ix, jx)| oldest_cofacet[ix] == Some(jx))
+        .collect()
+}
+
+/// Reduces a matrix by column additions, one group of columns at a time.
+///
+/// This is the standard persistence algorithm with the clearing (twist) optimization of Chen and
+/// Kerber. Groups hold the columns of a single dimension and are processed in the given order,
+/// which must be such that the pivots of a group lie in the columns of groups processed later
+/// (i.e. from the highest dimension down for boundary matrices). Any column whose index is
+/// already the pivot of a previously processed column must reduce to zero, so it is cleared
+/// without any column additions. The chain of a cleared column is taken to be the reduced column
+/// that killed it, which is a cycle with the same pivot, so that `R = D V` still holds. Columns
+/// that are already zero are left untouched, keeping the chain as the column's own index.
+///
+/// Columns known in advance to be reduced (e.g. from apparent pairs) can be given together with
+/// their pivots, in which case they are skipped and their pivots are claimed up front.
+///
+/// # Arguments
+///
+/// * `columns` - Matrix columns, in filtration order
+/// * `groups` - Indices of the columns in each group (in ascending order), in processing order
+/// * `reduced_pivots` - (pivot, column) for columns that are already reduced
+/// * `track_chains` - Whether to record column operations so that chains can be reconstructed
+/// * `field` - Field of coefficients
+pub(crate) fn reduce<F: Field>(
+    columns: Vec<SparseColumn>,
+    groups: &[Vec<usize>],
+    reduced_pivots: &[(usize, usize)],
+    track_chains: bool,
+    field: &F,
+) -> Reduction {
+    let n = columns.len();
+    let mut reduced = columns;
+    let mut pivot_column: Vec<Option<usize>> = vec![None; n];
+    let mut cleared = vec![false; n];
+    let mut operations: Option<Vec<Vec<(usize, u64)>>> = track_chains.then(|| vec![Vec::new(); n]);
+
+    let mut is_reduced = vec![false; n];
+    for &(pivot, jx) in reduced_pivots {
+        pivot_column[pivot] = Some(jx);
+        is_reduced[jx] = true;
+    }
+
+    for group in groups {
+        for &jx in group {
+            if is_reduced[jx] || reduced[jx].is_empty() {
+                // Nothing to reduce or clear (e.g. vertices), the chain is the column itself
+                continue;
+            }
+
+            if pivot_column[jx].is_some() {
+                // Already known to be a cycle that is killed by a later column
+                reduced[jx] = SparseColumn::default();
+                cleared[jx] = true;
+                continue;
+            }
+
+            while let Some((pivot, value)) = reduced[jx].pivot_entry() {
+                match pivot_column[pivot] {
+                    Some(kx) => {
+                        // Pivots are claimed by earlier columns of the same group, so kx < jx
+                        let (_, other_value) = reduced[kx].pivot_entry().unwrap();
+                        let factor = field.neg(field.mul(value, field.inv(other_value)));
+                        let (left, right) = reduced.split_at_mut(jx);
+                        right[0].add_scaled(&left[kx], factor, field);
+                        if let Some(operations) = operations.as_mut() {
+                            operations[jx].push((kx, factor));
+                        }
+                    },
+                    None => {
+                        pivot_column[pivot] = Some(jx);
+                        break;
+                    },
+                }
+            }
+        }
+    }
+
+    Reduction { reduced, pivot_column, cleared, operations }
+}
+
+/// Computes persistence pairs by reducing the boundary matrix.
+///
+/// # Arguments
+///
+/// * `boundaries` - Boundary columns, in filtration order
+/// * `dims` - Dimension of the chain corresponding to each column
+/// * `options` - Options controlling the computation
+/// * `field` - Field of coefficients
+pub(crate) fn persistent_homology<F: Field>(
+    boundaries: Vec<SparseColumn>,
+    dims: &[usize],
+    options: &PersistenceOptions,
+    field: &F,
+) -> PersistenceReduction {
+    let groups: Vec<Vec<usize>> = columns_by_dim(dims).into_iter().rev().collect();
+    let apparent = if options.apparent_pairs { apparent_pairs(&boundaries) } else { Vec::new() };
+    debug!("Found {} apparent pairs", apparent.len());
+    let track_chains = options.representatives != Representatives::None;
+    let reduction = reduce(boundaries, &groups, &apparent, track_chains, field);
+
+    let mut pairs = Vec::new();
+    for (death, column) in reduction.reduced.iter().enumerate() {
+        if let Some(birth) = column.pivot() {
+            pairs.push(PersistencePair { birth, death: Some(death) });
+        }
+    }
+    for (birth, column) in reduction.reduced.iter().enumerate() {
+        if column.is_empty() && reduction.pivot_column[birth].is_none() {
+            pairs.push(PersistencePair { birth, death: None });
+        }
+    }
+
+    PersistenceReduction { pairs, reduction, cohomology: false }
+}
+
+/// Computes persistence pairs by reducing the coboundary matrix.
+///
+/// The coboundary matrix is the anti-transpose of the boundary matrix, so its columns are
+/// indexed in reverse filtration order and are processed from the lowest dimension up, as in
+/// Ripser. The pairs are the same as for homology and are returned in the same order.
+///
+/// # Arguments
+///
+/// * `boundaries` - Boundary columns, in filtration order
+/// * `dims` - Dimension of the chain corresponding to each column
+/// * `options` - Options controlling the computation
+/// * `field` - Field of coefficients
+pub(crate) fn persistent_cohomology<F: Field>(
+    boundaries: Vec<SparseColumn>,
+    dims: &[usize],
+    options: &PersistenceOptions,
+    field: &F,
+) -> PersistenceReduction {
+    let n = boundaries.len();
+    let reverse = |ix: usize| n - 1 - ix;
+
+    // The coboundary column of the facet of an apparent pair is reduced, with the cofacet as pivot
+    let apparent: Vec<(usize, usize)> = if options.apparent_pairs {
+        apparent_pairs(&boundaries).into_iter().map(|(ix, jx)| (reverse(jx), reverse(ix))).collect()
+    } else {
+        Vec::new()
+    };
+    debug!("Found {} apparent pairs", apparent.len());
+
+    // Transpose the boundary matrix, reversing the order of rows and columns
+    let mut coboundaries: Vec<Vec<(usize, u64)>> = vec![Vec::new(); n];
+    for (jx, column) in boundaries.iter().enumerate().rev() {
+        for &(ix, value) in column.entries() {
+            coboundaries[reverse(ix)].push((reverse(jx), value));
+        }
+    }
+    let columns: Vec<SparseColumn> = coboundaries.into_iter().map(|entries| SparseColumn { entries }).collect();
+
+    let groups: Vec<Vec<usize>> =
+        columns_by_dim(dims).into_iter().map(|group| group.into_iter().rev().map(reverse).collect()).collect();
+    let track_chains = options.representatives != Representatives::None;
+    let reduction = reduce(columns, &groups, &apparent, track_chains, field);
+
+    let mut pairs = Vec::new();
+    for (column_ix, column) in reduction.reduced.iter().enumerate() {
+        if let Some(pivot) = column.pivot() {
+            pairs.push(PersistencePair { birth: reverse(column_ix), death: Some(reverse(pivot)) });
+        }
+    }
+    pairs.sort_by_key(|pair| pair.death);
+    for birth in 0..n {
+        let column_ix = reverse(birth);
+        if reduction.reduced[column_ix].is_empty() && reduction.pivot_column[column_ix].is_none() {
+            pairs.push(PersistencePair { birth, death: None });
+        }
+    }
+
+    PersistenceReduction { pairs, reduction, cohomology: true }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{PrimeField, Z2};
+
+    #[test]
+    fn test_column_addition() {
+        let mut a = SparseColumn::from(HashSet::from([1, 3, 5]));
+        let b = SparseColumn::from(HashSet::from([0, 3, 6]));
+        a.add_scaled(&b, 1, &Z2);
+        assert_eq!(a.indices().collect::<Vec<_>>(), vec![0, 1, 5, 6]);
+        assert_eq!(a.pivot(), Some(6));
+
+        a.add_scaled(&a.clone(), 1, &Z2);
+        assert!(a.is_empty());
+        assert_eq!(a.pivot(), None);
+    }
+
+    #[test]
+    fn test_column_addition_prime_field() {
+        let field = PrimeField::new(3).unwrap();
+        let mut a = SparseColumn::from_entries(vec![(3, 1), (1, -1), (3, 1)], &field);
+        assert_eq!(a.entries().copied().collect::<Vec<_>>(), vec![(1, 2), (3, 2)]);
+
+        let b = SparseColumn::from_entries(vec![(0, 1), (3, 1)], &field);
+        a.add_scaled(&b, 1, &field);
+        assert_eq!(a.entries().copied().collect::<Vec<_>>(), vec![(0, 1), (1, 2)]);
+        assert_eq!(a.pivot_entry(), Some((1, 2)));
+    }
+
+    #[test]
+    fn test_reduce_triangle() {
+        // Vertices 0, 1, 2; edges 01, 12, 02; triangle 012
+        let columns = vec![
+            SparseColumn::default(),
+            SparseColumn::default(),
+            SparseColumn::default(),
+            SparseColumn::from(HashSet::from([0, 1])),
+            SparseColumn::from(HashSet::from([1, 2])),
+            SparseColumn::from(HashSet::from([0, 2])),
+            SparseColumn::from(HashSet::from([3, 4, 5])),
+        ];
+        let reduction = reduce(columns, &[vec![6], vec![3, 4, 5], vec![0, 1, 2]], &[], true, &Z2);
+
+        assert_eq!(reduction.reduced[3].pivot(), Some(1));
+        assert_eq!(reduction.reduced[4].pivot(), Some(2));
+        assert!(reduction.reduced[5].is_empty());
+        assert_eq!(reduction.chains(&[5], &Z2)[0].indices().collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!(reduction.reduced[6].pivot(), Some(5));
+        assert_eq!(reduction.pivot_column[5], Some(6));
+        assert_eq!(reduction.pivot_column[0], None);
+    }
+
+    #[test]
+    fn test_clearing() {
+        // Two triangles 013 and 123 sharing the edge 13 (a square with a diagonal)
+        let columns = vec![
+            SparseColumn::default(),
+            SparseColumn::default(),
+            SparseColumn::default(),
+            SparseColumn::default(),
+            SparseColumn::from(HashSet::from([0, 1])),
+            SparseColumn::from(HashSet::from([1, 2])),
+            SparseColumn::from(HashSet::from([2, 3])),
+            SparseColumn::from(HashSet::from([0, 3])),
+            SparseColumn::from(HashSet::from([1, 3])),
+            SparseColumn::from(HashSet::from([4, 7, 8])),
+            SparseColumn::from(HashSet::from([5, 6, 8])),
+        ];
+        let reduction = reduce(columns, &[vec![9, 10], vec![4, 5, 6, 7, 8], vec![0, 1, 2, 3]], &[], true, &Z2);
+
+        // Edges 03 and 13 are cleared, their chains are the cycles killed by the triangles
+        assert_eq!(reduction.pivot_column[8], Some(9));
+        assert_eq!(reduction.pivot_column[7], Some(10));
+        assert!(reduction.reduced[7].is_empty());
+        assert!(reduction.reduced[8].is_empty());
+        let chains = reduction.chains(&[8, 7], &Z2);
+        assert_eq!(chains[0].indices().collect::<Vec<_>>(), vec![4, 7, 8]);
+        assert_eq!(chains[1].indices().collect::<Vec<_>>(), vec![4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_apparent_pairs() {
+        // Square 0123 with diagonal 13 and triangles 013, 123
+        let columns = vec![
+            SparseColumn::default(),
+            SparseColumn::default(),
+            SparseColumn::default(),
+            SparseColumn::default(),
+            SparseColumn::from(HashSet::from([0, 1])),
+            SparseColumn::from(HashSet::from([1, 2])),
+            SparseColumn::from(HashSet::from([2, 3])),
+            SparseColumn::from(HashSet::from([0, 3])),
+            SparseColumn::from(HashSet::from([1, 3])),
+            SparseColumn::from(HashSet::from([4, 7, 8])),
+            SparseColumn::from(HashSet::from([5, 6, 8])),
+        ];
+        // Triangle 123 shares its youngest facet 13 with the older triangle 013, so is not apparent
+        assert_eq!(apparent_pairs(&columns), vec![(1, 4), (2, 5), (3, 6), (8, 9)]);
+
+        let groups = [vec![9, 10], vec![4, 5, 6, 7, 8], vec![0, 1, 2, 3]];
+        let with_apparent = reduce(columns.clone(), &groups, &apparent_pairs(&columns), true, &Z2);
+        let without = reduce(columns, &groups, &[], true, &Z2);
+        let all: Vec<usize> = (0..11).collect();
+        assert_eq!(with_apparent.reduced, without.reduced);
+        assert_eq!(with_apparent.chains(&all, &Z2), without.chains(&all, &Z2));
+        assert_eq!(with_apparent.pivot_column, without.pivot_column);
+    }
+
+    #[test]
+    fn test_untracked_chains() {
+        let columns = vec![SparseColumn::default(), SparseColumn::default(), SparseColumn::from(HashSet::from([0, 1]))];
+        let reduction = reduce(columns, &[vec![2], vec![0, 1]], &[], false, &Z2);
+        assert_eq!(reduction.pivot_column[1], Some(2));
+        assert!(std::panic::catch_unwind(|| reduction.chains(&[2], &Z2)).is_err());
+    }
+}
@@ -0,0 +1,459 @@
+use log::debug;
+use ordered_float::OrderedFloat;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::OnceLock;
+
+use super::combinatorics::generate_subsets;
+use super::homology::{Chain, ChainComplex};
+use super::simplicial_complex::{Simplex, SimplicialComplex, SimplicialComplexError};
+
+/// Index of the root node, which represents the empty simplex.
+const ROOT: usize = 0;
+
+/// A node of the simplex tree, representing the simplex spelled by the vertices on the path
+/// from the root.
+#[derive(Debug, Clone)]
+struct Node {
+    /// Last vertex of the simplex
+    vertex: usize,
+    /// Parent node, i.e. the simplex without its last vertex
+    parent: usize,
+    /// Number of vertices of the simplex
+    depth: usize,
+    /// Filtration level of the simplex
+    level: f64,
+    /// Children nodes by vertex
+    children: BTreeMap<usize, usize>,
+}
+
+/// A simplex of a [`SimplexTree`], referring to its node instead of storing its vertices.
+///
+/// Use [`SimplexTree::simplex`] to recover the vertices.
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+pub struct SimplexNode {
+    /// Node of the simplex in the tree
+    node: usize,
+    /// Dimension of the simplex
+    pub dim: usize,
+}
+
+impl Chain for SimplexNode {
+    fn dim(&self) -> usize {
+        self.dim
+    }
+}
+
+/// Simplices of the tree in filtration order, computed on demand.
+#[derive(Debug, Clone)]
+struct Filtration {
+    chains: Vec<SimplexNode>,
+    levels: Vec<f64>,
+    /// Index in the filtration of each node
+    indices: Vec<usize>,
+}
+
+/// A filtered simplicial complex stored as a simplex tree, i.e. a trie over the sorted vertices
+/// of its simplices (Boissonnat and Maria).
+///
+/// Each simplex is a single node, so faces are found by walking the trie instead of hashing
+/// vertex lists, and cofaces by following the nodes with a given last vertex. Simplices are
+/// always inserted together with their faces, so the complex is closed and its filtration
+/// valid by construction.
+#[derive(Debug, Clone)]
+pub struct SimplexTree {
+    nodes: Vec<Node>,
+    /// Nodes by last vertex, used to find cofaces
+    by_vertex: HashMap<usize, Vec<usize>>,
+    /// Filtration order, reset on insertion
+    filtration: OnceLock<Filtration>,
+}
+
+impl Default for SimplexTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimplexTree {
+    /// Creates an empty simplex tree.
+    pub fn new() -> Self {
+        let root =
+            Node { vertex: usize::MAX, parent: ROOT, depth: 0, level: f64::NEG_INFINITY, children: BTreeMap::new() };
+        Self { nodes: vec![root], by_vertex: HashMap::new(), filtration: OnceLock::new() }
+    }
+
+    /// Inserts a simplex and all of its faces at the given filtration level.
+    ///
+    /// Simplices already in the tree keep the smaller of their level and the new one, so that
+    /// faces never appear after their cofaces.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertices` - Vertices of the simplex, in any order
+    /// * `level` - Filtration level of the simplex
+    ///
+    /// # Returns
+    ///
+    /// A Result which is an error if the simplex is invalid or the level is NaN.
+    pub fn insert(&mut self, vertices: &[usize], level: f64) -> Result<(), SimplicialComplexError> {
+        let simplex = Simplex::new(vertices.to_vec());
+        simplex.validate()?;
+        if level.is_nan() {
+            return Err(SimplicialComplexError::InvalidLevel { simplex: simplex.vertices, level });
+        }
+        self.filtration = OnceLock::new();
+
+        // Subsets are generated by size, so the parent of each face is inserted before it
+        for face in generate_subsets(&simplex.vertices, simplex.vertices.len()).into_iter().skip(1) {
+            let (&last, prefix) = face.split_last().unwrap();
+            let parent = self.find_node(prefix).unwrap();
+            match self.nodes[parent].children.get(&last) {
+                Some(&node) => self.nodes[node].level = self.nodes[node].level.min(level),
+                None => {
+                    let node = self.nodes.len();
+                    let depth = self.nodes[parent].depth + 1;
+                    self.nodes.push(Node { vertex: last, parent, depth, level, children: BTreeMap::new() });
+                    self.nodes[parent].children.insert(last, node);
+                    self.by_vertex.entry(last).or_default().push(node);
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the node of a simplex given by its sorted vertices.
+    fn find_node(&self, vertices: &[usize]) -> Option<usize> {
+        vertices.iter().try_fold(ROOT, |node, v| self.nodes[node].children.get(v).copied())
+    }
+
+    /// Returns the sorted vertices of the simplex of a node.
+    fn vertices(&self, mut node: usize) -> Vec<usize> {
+        let mut vertices = Vec::with_capacity(self.nodes[node].depth);
+        while node != ROOT {
+            vertices.push(self.nodes[node].vertex);
+            node = self.nodes[node].parent;
+        }
+        vertices.reverse();
+        vertices
+    }
+
+    /// Compares the simplices of two nodes of the same depth lexicographically.
+    ///
+    /// Both paths reach their common ancestor at the same time, and the vertices just below it
+    /// decide the order.
+    fn compare_vertices(&self, mut a: usize, mut b: usize) -> Ordering {
+        let mut ordering = Ordering::Equal;
+        while a != b {
+            ordering = self.nodes[a].vertex.cmp(&self.nodes[b].vertex);
+            a = self.nodes[a].parent;
+            b = self.nodes[b].parent;
+        }
+        ordering
+    }
+
+    /// Appends the nodes of the facets of a simplex, the kth one omitting its kth vertex.
+    ///
+    /// The facets of a simplex are those of its parent extended by its last vertex, followed
+    /// by the parent itself.
+    fn push_facets(&self, node: usize, facets: &mut Vec<usize>) {
+        let Node { vertex, parent, .. } = self.nodes[node];
+        if parent != ROOT {
+            let start = facets.len();
+            self.push_facets(parent, facets);
+            for facet in &mut facets[start..] {
+                *facet = self.nodes[*facet].children[&vertex];
+            }
+        }
+        facets.push(parent);
+    }
+
+    /// Returns the nodes of the subtree rooted at a node, including it.
+    fn subtree(&self, node: usize) -> Vec<usize> {
+        let mut nodes = vec![node];
+        let mut ix = 0;
+        while ix < nodes.len() {
+            nodes.extend(self.nodes[nodes[ix]].children.values());
+            ix += 1;
+        }
+        nodes
+    }
+
+    /// Returns the simplex of a chain of the tree.
+    pub fn simplex(&self, chain: &SimplexNode) -> Simplex {
+        Simplex { vertices: self.vertices(chain.node) }
+    }
+
+    /// Returns true if the tree contains the simplex with the given vertices.
+    pub fn contains(&self, vertices: &[usize]) -> bool {
+        self.filtration_value(vertices).is_some()
+    }
+
+    /// Returns the filtration level of the simplex with the given vertices, if it exists.
+    pub fn filtration_value(&self, vertices: &[usize]) -> Option<f64> {
+        let simplex = Simplex::new(vertices.to_vec());
+        self.find_node(&simplex.vertices).filter(|&node| node != ROOT).map(|node| self.nodes[node].level)
+    }
+
+    /// Returns the number of simplices in the tree.
+    pub fn n_simplices(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    /// Returns the dimension of the largest simplex, or None if the tree is empty.
+    pub fn dimension(&self) -> Option<usize> {
+        self.nodes.iter().map(|n| n.depth).max().filter(|&d| d > 0).map(|d| d - 1)
+    }
+
+    /// Returns the cofaces of a simplex, i.e. the simplices strictly containing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertices` - Vertices of the simplex, in any order
+    ///
+    /// # Returns
+    ///
+    /// The cofaces with their filtration levels, empty if the simplex is not in the tree.
+    pub fn cofaces(&self, vertices: &[usize]) -> Vec<(Simplex, f64)> {
+        let simplex = Simplex::new(vertices.to_vec());
+        self.star(&simplex.vertices).into_iter().filter(|(s, _)| *s != simplex).collect()
+    }
+
+    /// Returns the closed star of a simplex as a list of simplices: the simplex and its
+    /// cofaces, together with all of their faces.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertices` - Vertices of the simplex, in any order
+    ///
+    /// # Returns
+    ///
+    /// The simplices of the closed star with their filtration levels, empty if the simplex is
+    /// not in the tree.
+    pub fn closed_star(&self, vertices: &[usize]) -> Vec<(Simplex, f64)> {
+        let mut seen = HashSet::new();
+        let mut closed = Vec::new();
+        for (coface, _) in self.star(vertices) {
+            for face in generate_subsets(&coface.vertices, coface.vertices.len()).into_iter().skip(1) {
+                if seen.insert(face.clone()) {
+                    let level = self.nodes[self.find_node(&face).unwrap()].level;
+                    closed.push((Simplex { vertices: face }, level));
+                }
+            }
+        }
+        closed
+    }
+
+    /// Returns the (open) star of a simplex, i.e. the simplex and all of its cofaces.
+    ///
+    /// Cofaces are found from the nodes whose last vertex is the last vertex of the simplex:
+    /// every coface extends exactly one of them that contains the simplex.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertices` - Vertices of the simplex, in any order
+    ///
+    /// # Returns
+    ///
+    /// The simplices of the star with their filtration levels, empty if the simplex is not in
+    /// the tree.
+    pub fn star(&self, vertices: &[usize]) -> Vec<(Simplex, f64)> {
+        let simplex = Simplex::new(vertices.to_vec());
+        let Some(&last) = simplex.vertices.last() else {
+            return Vec::new();
+        };
+        let mut star = Vec::new();
+        for &node in self.by_vertex.get(&last).into_iter().flatten() {
+            if self.nodes[node].depth < simplex.vertices.len() {
+                continue;
+            }
+            let path = self.vertices(node);
+            if simplex.vertices.iter().all(|v| path.binary_search(v).is_ok()) {
+                star.extend(
+                    self.subtree(node)
+                        .into_iter()
+                        .map(|n| (Simplex { vertices: self.vertices(n) }, self.nodes[n].level)),
+                );
+            }
+        }
+        star
+    }
+
+    /// Returns the link of a simplex, i.e. the simplices disjoint from it whose join with it
+    /// is in the tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertices` - Vertices of the simplex, in any order
+    ///
+    /// # Returns
+    ///
+    /// The simplices of the link with the filtration levels of their joins with the simplex.
+    pub fn link(&self, vertices: &[usize]) -> Vec<(Simplex, f64)> {
+        let simplex = Simplex::new(vertices.to_vec());
+        self.cofaces(&simplex.vertices)
+            .into_iter()
+            .map(|(coface, level)| {
+                let vertices =
+                    coface.vertices.into_iter().filter(|v| simplex.vertices.binary_search(v).is_err()).collect();
+                (Simplex { vertices }, level)
+            })
+            .collect()
+    }
+
+    /// Returns the simplices in filtration order, computing it if needed.
+    ///
+    /// Simplices are ordered as in [`SimplicialComplex::new`]: by level, then dimension, then
+    /// lexicographically.
+    fn filtration(&self) -> &Filtration {
+        self.filtration.get_or_init(|| {
+            debug!("Ordering simplex tree with {} simplices", self.n_simplices());
+            let mut nodes: Vec<usize> = (1..self.nodes.len()).collect();
+            nodes.sort_by(|&a, &b| {
+                let (x, y) = (&self.nodes[a], &self.nodes[b]);
+                OrderedFloat(x.level)
+                    .cmp(&OrderedFloat(y.level))
+                    .then(x.depth.cmp(&y.depth))
+                    .then_with(|| self.compare_vertices(a, b))
+            });
+
+            let mut indices = vec![usize::MAX; self.nodes.len()];
+            for (ix, &node) in nodes.iter().enumerate() {
+                indices[node] = ix;
+            }
+            let chains = nodes.iter().map(|&node| SimplexNode { node, dim: self.nodes[node].depth - 1 }).collect();
+            let levels = nodes.iter().map(|&node| self.nodes[node].level).collect();
+            Filtration { chains, levels, indices }
+        })
+    }
+}
+
+impl From<&SimplicialComplex> for SimplexTree {
+    fn from(complex: &SimplicialComplex) -> Self {
+        let mut tree = Self::new();
+        for (simplex, &level) in complex.simplices.iter().zip(&complex.levels) {
+            // Simplices and levels of a complex are already valid
+            tree.insert(&simplex.vertices, level).unwrap();
+        }
+        tree
+    }
+}
+
+impl ChainComplex<SimplexNode> for SimplexTree {
+    fn chain(&self, index: usize) -> &SimplexNode {
+        &self.filtration().chains[index]
+    }
+
+    fn chains(&self) -> &Vec<SimplexNode> {
+        &self.filtration().chains
+    }
+
+    fn filtration_level(&self, index: usize) -> OrderedFloat<f64> {
+        OrderedFloat(self.filtration().levels[index])
+    }
+
+    fn boundary(&self, index: usize) -> HashSet<usize> {
+        self.signed_boundary(index).into_iter().map(|(face, _)| face).collect()
+    }
+
+    fn signed_boundary(&self, index: usize) -> Vec<(usize, i64)> {
+        let filtration = self.filtration();
+        let chain = filtration.chains[index];
+        if chain.dim == 0 {
+            return Vec::new();
+        }
+
+        // The face obtained by removing the kth vertex has sign (-1)^k
+        let mut facets = Vec::with_capacity(chain.dim + 1);
+        self.push_facets(chain.node, &mut facets);
+        facets
+            .into_iter()
+            .enumerate()
+            .map(|(k, facet)| (filtration.indices[facet], if k % 2 == 0 { 1 } else { -1 }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::circle_cloud;
+
+    fn sorted(mut simplices: Vec<(Simplex, f64)>) -> Vec<(Vec<usize>, f64)> {
+        simplices.sort_by(|a, b| a.0.vertices.cmp(&b.0.vertices));
+        simplices.into_iter().map(|(s, l)| (s.vertices, l)).collect()
+    }
+
+    /// Two triangles sharing the edge 12, and a dangling edge 34.
+    fn tree() -> SimplexTree {
+        let mut tree = SimplexTree::new();
+        tree.insert(&[2, 1, 0], 2.0).unwrap();
+        tree.insert(&[1, 2, 3], 1.0).unwrap();
+        tree.insert(&[3, 4], 3.0).unwrap();
+        tree.insert(&[0], 0.5).unwrap();
+        tree
+    }
+
+    #[test]
+    fn test_insert() {
+        let tree = tree();
+        assert_eq!(tree.n_simplices(), 13);
+        assert_eq!(tree.dimension(), Some(2));
+        assert_eq!(tree.filtration_value(&[0]), Some(0.5));
+        assert_eq!(tree.filtration_value(&[2, 1]), Some(1.0));
+        assert_eq!(tree.filtration_value(&[0, 1]), Some(2.0));
+        assert!(tree.contains(&[4, 3]));
+        assert!(!tree.contains(&[0, 3]));
+        assert!(!tree.contains(&[]));
+
+        let mut empty = SimplexTree::new();
+        assert_eq!(empty.dimension(), None);
+        assert!(empty.insert(&[0, 0], 1.0).is_err());
+        assert!(matches!(empty.insert(&[0], f64::NAN), Err(SimplicialComplexError::InvalidLevel { .. })));
+    }
+
+    #[test]
+    fn test_star_and_link() {
+        let tree = tree();
+        assert_eq!(sorted(tree.cofaces(&[2, 1])), vec![(vec![0, 1, 2], 2.0), (vec![1, 2, 3], 1.0)]);
+        assert_eq!(
+            sorted(tree.star(&[3])),
+            vec![(vec![1, 2, 3], 1.0), (vec![1, 3], 1.0), (vec![2, 3], 1.0), (vec![3], 1.0), (vec![3, 4], 3.0)]
+        );
+        assert_eq!(sorted(tree.closed_star(&[4])), vec![(vec![3], 1.0), (vec![3, 4], 3.0), (vec![4], 3.0)]);
+        assert_eq!(
+            sorted(tree.link(&[1])),
+            vec![(vec![0], 2.0), (vec![0, 2], 2.0), (vec![2], 1.0), (vec![2, 3], 1.0), (vec![3], 1.0)]
+        );
+        assert!(tree.star(&[5]).is_empty());
+    }
+
+    #[test]
+    fn test_filtration_order() {
+        let tree = tree();
+        let simplices: Vec<Simplex> = tree.chains().iter().map(|chain| tree.simplex(chain)).collect();
+        let complex = SimplicialComplex::new(
+            simplices.clone(),
+            (0..tree.len()).map(|ix| tree.filtration_level(ix).into_inner()).collect(),
+        )
+        .unwrap();
+        assert_eq!(simplices, complex.simplices);
+        assert!(tree.chains().iter().zip(&simplices).all(|(chain, simplex)| chain.dim == simplex.dim()));
+        for ix in 0..tree.len() {
+            let mut expected = complex.signed_boundary(ix);
+            expected.sort();
+            let mut boundary = tree.signed_boundary(ix);
+            boundary.sort();
+            assert_eq!(boundary, expected);
+        }
+    }
+
+    #[test]
+    fn test_persistence_matches_simplicial_complex() {
+        // Points on a noisy circle
+        let complex = circle_cloud(10, 0.1).vietoris_rips_complex(2, 1.5).unwrap();
+        let tree = SimplexTree::from(&complex);
+        assert_eq!(tree.len(), complex.len());
+        assert_eq!(tree.persistence_intervals(), complex.persistence_intervals());
+    }
+}
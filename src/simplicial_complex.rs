@@ -1,5 +1,8 @@
+use super::combinatorics::{BinomialTable, Combinations, CombinatoricsError};
 use super::homology::{Chain, ChainComplex};
+use log::debug;
 use ordered_float::OrderedFloat;
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
@@ -10,6 +13,16 @@ pub enum SimplicialComplexError {
     LengthMismatch { n_simplices: usize, n_levels: usize },
     #[error("Invalid simplex: {0}")]
     InvalidSimplex(String),
+    #[error("Invalid filtration level {level} for simplex {simplex:?}")]
+    InvalidLevel { simplex: Vec<usize>, level: f64 },
+    #[error("Simplex {0:?} appears more than once")]
+    DuplicateSimplex(Vec<usize>),
+    #[error("Face {face:?} of simplex {simplex:?} is missing from the complex")]
+    MissingFace { simplex: Vec<usize>, face: Vec<usize> },
+    #[error("Complexes to join share vertex {0}")]
+    SharedVertex(usize),
+    #[error("Face {face:?} appears at level {face_level}, after its coface {simplex:?} at level {simplex_level}")]
+    FaceAfterCoface { simplex: Vec<usize>, simplex_level: f64, face: Vec<usize>, face_level: f64 },
 }
 
 /// A simplex defined by its vertices.
@@ -44,7 +57,7 @@ impl Simplex {
     /// A simplex is well-formed if:
     /// - It has at least one vertex
     /// - All vertices are unique
-    fn validate(&self) -> Result<(), SimplicialComplexError> {
+    pub(crate) fn validate(&self) -> Result<(), SimplicialComplexError> {
         if self.vertices.is_empty() {
             return Err(SimplicialComplexError::InvalidSimplex("Simplex must have at least one vertex".to_string()));
         }
@@ -58,6 +71,67 @@ impl Simplex {
         }
         Ok(())
     }
+
+    /// Returns the key of the simplex in the combinatorial number system, which identifies it
+    /// among the simplices of the same dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - Binomial coefficients covering the vertices and dimension of the simplex
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the key or an error if the simplex is not covered by the table.
+    pub fn rank(&self, table: &BinomialTable) -> Result<u64, CombinatoricsError> {
+        table.rank(&self.vertices)
+    }
+
+    /// Creates the simplex of the given dimension with the given key in the combinatorial
+    /// number system.
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - Key of the simplex, as returned by [`Self::rank`]
+    /// * `dim` - Dimension of the simplex
+    /// * `table` - Binomial coefficients covering the vertices and dimension of the simplex
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the simplex or an error if the key or dimension is not covered
+    /// by the table.
+    pub fn unrank(rank: u64, dim: usize, table: &BinomialTable) -> Result<Self, CombinatoricsError> {
+        table.unrank(rank, dim + 1).map(|vertices| Self { vertices })
+    }
+
+    /// Returns the faces of the given codimension, in lexicographic order.
+    ///
+    /// The face of codimension 0 is the simplex itself, and there are no faces of codimension
+    /// greater than or equal to the number of vertices.
+    ///
+    /// # Arguments
+    ///
+    /// * `codimension` - Number of vertices to remove
+    pub fn faces(&self, codimension: usize) -> impl Iterator<Item = Simplex> + '_ {
+        let size = self.vertices.len().checked_sub(codimension).filter(|&size| size > 0);
+        size.and_then(|size| Combinations::new(&self.vertices, size).ok())
+            .into_iter()
+            .flatten()
+            .map(|vertices| Simplex { vertices })
+    }
+
+    /// Returns true if this simplex is a face of the other, including the other itself.
+    pub fn is_face_of(&self, other: &Simplex) -> bool {
+        self.vertices.iter().all(|v| other.vertices.binary_search(v).is_ok())
+    }
+
+    /// Returns the join of two simplices, i.e. the simplex spanned by the vertices of both, or
+    /// None if they share a vertex.
+    pub fn join(&self, other: &Simplex) -> Option<Simplex> {
+        if self.vertices.iter().any(|v| other.vertices.binary_search(v).is_ok()) {
+            return None;
+        }
+        Some(Simplex::new(self.vertices.iter().chain(&other.vertices).copied().collect()))
+    }
 }
 
 impl Chain for Simplex {
@@ -81,11 +155,17 @@ pub struct SimplicialComplex {
     pub levels: Vec<f64>,
     /// Map from simplex to its index in the complex
     indexes: HashMap<Simplex, usize>,
+    /// Indices of the simplices containing each vertex, in filtration order
+    by_vertex: HashMap<usize, Vec<usize>>,
 }
 
 impl SimplicialComplex {
     /// Creates a new simplicial complex with the given simplices and filtration levels.
     ///
+    /// Simplices are ordered by filtration level, then dimension, then lexicographically by
+    /// vertices, so that the order is reproducible and faces come before their cofaces.
+    /// Infinite levels are allowed, e.g. for simplices that never appear in a finite range.
+    ///
     /// # Arguments
     ///
     /// * `simplices` - Vector of simplices in the complex
@@ -95,36 +175,252 @@ impl SimplicialComplex {
     ///
     /// A Result containing either the new SimplicialComplex or an error if:
     /// - The number of simplices doesn't match the number of levels
-    /// - Any simplex is invalid
+    /// - Any simplex is invalid or appears more than once
+    /// - Any level is NaN
+    /// - A face of a simplex is missing (see [`Self::closure`] to add missing faces)
+    /// - A face appears at a later level than one of its cofaces
     pub fn new(simplices: Vec<Simplex>, levels: Vec<f64>) -> Result<Self, SimplicialComplexError> {
-        if simplices.len() != levels.len() {
-            return Err(SimplicialComplexError::LengthMismatch {
-                n_simplices: simplices.len(),
-                n_levels: levels.len(),
-            });
-        }
+        Self::with_tie_breaker(simplices, levels, |a, b| a.vertices.cmp(&b.vertices))
+    }
 
-        // Validate all simplices
-        for simplex in &simplices {
-            simplex.validate()?;
+    /// Creates a new simplicial complex, ordering simplices with the same level and dimension
+    /// with the given comparison instead of lexicographically.
+    ///
+    /// # Arguments
+    ///
+    /// * `simplices` - Vector of simplices in the complex
+    /// * `levels` - Vector of filtration levels, one for each simplex
+    /// * `tie_breaker` - Total order on simplices of the same level and dimension
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the new SimplicialComplex or an error, as for [`Self::new`].
+    pub fn with_tie_breaker<F: Fn(&Simplex, &Simplex) -> Ordering>(
+        simplices: Vec<Simplex>,
+        levels: Vec<f64>,
+        tie_breaker: F,
+    ) -> Result<Self, SimplicialComplexError> {
+        validate_inputs(&simplices, &levels)?;
+
+        // Check that the complex is closed under faces and that faces appear first
+        let mut given: HashMap<&Simplex, f64> = HashMap::with_capacity(simplices.len());
+        for (simplex, &level) in simplices.iter().zip(&levels) {
+            if given.insert(simplex, level).is_some() {
+                return Err(SimplicialComplexError::DuplicateSimplex(simplex.vertices.clone()));
+            }
+        }
+        for (simplex, &level) in simplices.iter().zip(&levels) {
+            for face in simplex.faces(1) {
+                match given.get(&face) {
+                    None => {
+                        return Err(SimplicialComplexError::MissingFace {
+                            simplex: simplex.vertices.clone(),
+                            face: face.vertices,
+                        })
+                    },
+                    Some(&face_level) if face_level > level => {
+                        return Err(SimplicialComplexError::FaceAfterCoface {
+                            simplex: simplex.vertices.clone(),
+                            simplex_level: level,
+                            face: face.vertices,
+                            face_level,
+                        })
+                    },
+                    Some(_) => {},
+                }
+            }
         }
 
-        // Order simplices by filtration level
+        // Order simplices by filtration level, with faces first among simplices of the same level
         let mut paired: Vec<_> = simplices.into_iter().zip(levels).collect();
-        paired.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        paired.sort_by(|a, b| {
+            OrderedFloat(a.1)
+                .cmp(&OrderedFloat(b.1))
+                .then_with(|| a.0.dim().cmp(&b.0.dim()))
+                .then_with(|| tie_breaker(&a.0, &b.0))
+        });
         let (sorted_simplices, sorted_levels): (Vec<_>, Vec<_>) = paired.into_iter().unzip();
+        Ok(Self::from_sorted(sorted_simplices, sorted_levels))
+    }
 
+    /// Creates a complex from simplices already validated and in filtration order, indexing
+    /// them by vertices and by simplex.
+    fn from_sorted(simplices: Vec<Simplex>, levels: Vec<f64>) -> Self {
         // Construct mapping to be able to access "ith simplex"
-        let indexes: HashMap<Simplex, usize> =
-            sorted_simplices.iter().enumerate().map(|(i, s)| (s.clone(), i)).collect();
+        let indexes = simplices.iter().enumerate().map(|(i, s)| (s.clone(), i)).collect();
+        let mut by_vertex: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (i, simplex) in simplices.iter().enumerate() {
+            for &v in &simplex.vertices {
+                by_vertex.entry(v).or_default().push(i);
+            }
+        }
+        Self { simplices, levels, indexes, by_vertex }
+    }
+
+    /// Creates a simplicial complex from the given simplices and their faces.
+    ///
+    /// Faces missing from the given simplices are added with the largest level consistent with
+    /// the filtration, i.e. the smallest level of their cofaces. Faces that are given keep their
+    /// level, and must still appear no later than their cofaces.
+    ///
+    /// # Arguments
+    ///
+    /// * `simplices` - Vector of simplices in the complex, not necessarily closed under faces
+    /// * `levels` - Vector of filtration levels, one for each simplex
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the new SimplicialComplex or an error, as for [`Self::new`].
+    pub fn closure(simplices: Vec<Simplex>, levels: Vec<f64>) -> Result<Self, SimplicialComplexError> {
+        validate_inputs(&simplices, &levels)?;
+
+        let given: HashSet<&Simplex> = simplices.iter().collect();
+        let mut all: HashMap<Simplex, f64> = HashMap::new();
+        for (simplex, &level) in simplices.iter().zip(&levels) {
+            if all.insert(simplex.clone(), level).is_some() {
+                return Err(SimplicialComplexError::DuplicateSimplex(simplex.vertices.clone()));
+            }
+        }
 
-        Ok(Self { simplices: sorted_simplices, levels: sorted_levels, indexes })
+        // Levels of missing faces are only final once all of their cofaces have been seen
+        let max_dim = simplices.iter().map(|s| s.dim()).max().unwrap_or(0);
+        for dim in (1..=max_dim).rev() {
+            let current: Vec<(Simplex, f64)> =
+                all.iter().filter(|(s, _)| s.dim() == dim).map(|(s, &l)| (s.clone(), l)).collect();
+            for (simplex, level) in current {
+                for face in simplex.faces(1) {
+                    if !given.contains(&face) {
+                        let face_level = all.entry(face).or_insert(level);
+                        *face_level = face_level.min(level);
+                    }
+                }
+            }
+        }
+        debug!("Closure added {} faces to {} simplices", all.len() - simplices.len(), simplices.len());
+
+        let (simplices, levels) = all.into_iter().unzip();
+        Self::new(simplices, levels)
+    }
+
+    /// Returns the subcomplex of simplices appearing at or before the given filtration level.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - Maximum filtration level of the simplices to keep
+    pub fn truncate(&self, level: f64) -> Self {
+        let n = self.levels.iter().take_while(|&&l| l <= level).count();
+        Self::from_sorted(self.simplices[..n].to_vec(), self.levels[..n].to_vec())
     }
 
     /// Returns the index of a simplex in the complex, if it exists.
     pub fn index_of(&self, simplex: &Simplex) -> Option<usize> {
         self.indexes.get(simplex).copied()
     }
+
+    /// Returns the subcomplex of the simplices satisfying a predicate, which must keep the faces
+    /// of the simplices it keeps. The filtration order is preserved.
+    fn subcomplex<F: Fn(&Simplex) -> bool>(&self, keep: F) -> Self {
+        let (simplices, levels): (Vec<Simplex>, Vec<f64>) =
+            self.simplices.iter().zip(&self.levels).filter(|(s, _)| keep(s)).map(|(s, &l)| (s.clone(), l)).unzip();
+        Self::from_sorted(simplices, levels)
+    }
+
+    /// Returns the indices of the cofaces of a simplex, i.e. the simplices strictly containing
+    /// it, in filtration order.
+    pub fn cofaces(&self, simplex: &Simplex) -> Vec<usize> {
+        self.star(simplex)
+            .into_iter()
+            .filter(|&ix| self.simplices[ix].vertices.len() > simplex.vertices.len())
+            .collect()
+    }
+
+    /// Returns the indices of the (open) star of a simplex, i.e. the simplex and its cofaces,
+    /// in filtration order. The open star is not a subcomplex in general.
+    ///
+    /// Only the simplices containing the least frequent vertex of the simplex are searched.
+    pub fn star(&self, simplex: &Simplex) -> Vec<usize> {
+        let mut candidates: Option<&Vec<usize>> = None;
+        for v in &simplex.vertices {
+            match self.by_vertex.get(v) {
+                None => return Vec::new(),
+                Some(containing) if candidates.is_none_or(|c| containing.len() < c.len()) => {
+                    candidates = Some(containing)
+                },
+                Some(_) => {},
+            }
+        }
+        match candidates {
+            Some(containing) => {
+                containing.iter().copied().filter(|&ix| simplex.is_face_of(&self.simplices[ix])).collect()
+            },
+            None => (0..self.simplices.len()).collect(),
+        }
+    }
+
+    /// Returns the closed star of a simplex, i.e. the subcomplex of the simplices whose union
+    /// with it is in the complex, keeping their filtration levels.
+    pub fn closed_star(&self, simplex: &Simplex) -> Self {
+        self.subcomplex(|s| {
+            let mut union: Vec<usize> = s.vertices.iter().chain(&simplex.vertices).copied().collect();
+            union.sort_unstable();
+            union.dedup();
+            self.indexes.contains_key(&Simplex { vertices: union })
+        })
+    }
+
+    /// Returns the link of a simplex, i.e. the subcomplex of the simplices disjoint from it whose
+    /// join with it is in the complex, keeping their filtration levels.
+    pub fn link(&self, simplex: &Simplex) -> Self {
+        self.subcomplex(|s| s.join(simplex).is_some_and(|join| self.indexes.contains_key(&join)))
+    }
+
+    /// Returns the k-skeleton of the complex, i.e. the subcomplex of simplices of dimension at
+    /// most k.
+    pub fn skeleton(&self, k: usize) -> Self {
+        self.subcomplex(|s| s.dim() <= k)
+    }
+
+    /// Returns the join of two complexes on disjoint vertex sets, made of the simplices of both
+    /// and the joins of every pair of their simplices. Each join appears at the later of the
+    /// levels of its two parts.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Complex to join with, whose vertices must not be vertices of this complex
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the join or an error if the complexes share a vertex.
+    pub fn join(&self, other: &Self) -> Result<Self, SimplicialComplexError> {
+        let vertices: HashSet<usize> = self.simplices.iter().flat_map(|s| s.vertices.iter().copied()).collect();
+        if let Some(&shared) = other.simplices.iter().flat_map(|s| s.vertices.iter()).find(|v| vertices.contains(v)) {
+            return Err(SimplicialComplexError::SharedVertex(shared));
+        }
+
+        let mut simplices: Vec<Simplex> = self.simplices.iter().chain(&other.simplices).cloned().collect();
+        let mut levels: Vec<f64> = self.levels.iter().chain(&other.levels).copied().collect();
+        for (a, &a_level) in self.simplices.iter().zip(&self.levels) {
+            for (b, &b_level) in other.simplices.iter().zip(&other.levels) {
+                simplices.push(a.join(b).unwrap());
+                levels.push(a_level.max(b_level));
+            }
+        }
+        Self::new(simplices, levels)
+    }
+}
+
+/// Checks that there is one valid level per valid simplex.
+fn validate_inputs(simplices: &[Simplex], levels: &[f64]) -> Result<(), SimplicialComplexError> {
+    if simplices.len() != levels.len() {
+        return Err(SimplicialComplexError::LengthMismatch { n_simplices: simplices.len(), n_levels: levels.len() });
+    }
+    for (simplex, &level) in simplices.iter().zip(levels) {
+        simplex.validate()?;
+        if level.is_nan() {
+            return Err(SimplicialComplexError::InvalidLevel { simplex: simplex.vertices.clone(), level });
+        }
+    }
+    Ok(())
 }
 
 impl ChainComplex<Simplex> for SimplicialComplex {
@@ -158,6 +454,25 @@ impl ChainComplex<Simplex> for SimplicialComplex {
         }
         bounds
     }
+
+    fn signed_boundary(&self, index: usize) -> Vec<(usize, i64)> {
+        let s = &self.simplices[index];
+        let mut bounds = Vec::new();
+
+        // The face obtained by removing the kth vertex has sign (-1)^k
+        for k in 0..s.vertices.len() {
+            let mut face_vertices = s.vertices.clone();
+            face_vertices.remove(k);
+
+            if !face_vertices.is_empty() {
+                let face = Simplex { vertices: face_vertices };
+                if let Some(face_index) = self.index_of(&face) {
+                    bounds.push((face_index, if k % 2 == 0 { 1 } else { -1 }));
+                }
+            }
+        }
+        bounds
+    }
 }
 
 #[cfg(test)]
@@ -172,6 +487,106 @@ mod tests {
         assert!(Simplex::new(vec![0, 0]).validate().is_err());
     }
 
+    #[test]
+    fn test_simplex_keys() {
+        let table = BinomialTable::new(10, 3).unwrap();
+        let simplex = Simplex::new(vec![7, 2, 4]);
+        let key = simplex.rank(&table).unwrap();
+        assert_eq!(key, 35 + 6 + 2);
+        assert_eq!(Simplex::unrank(key, 2, &table).unwrap(), simplex);
+        assert!(Simplex::new(vec![0, 1, 2, 3]).rank(&table).is_err());
+        assert!(Simplex::unrank(0, 3, &table).is_err());
+    }
+
+    #[test]
+    fn test_simplex_operations() {
+        let simplex = Simplex::new(vec![3, 1, 2]);
+        let faces = |codimension| simplex.faces(codimension).map(|s| s.vertices).collect::<Vec<_>>();
+        assert_eq!(faces(0), vec![vec![1, 2, 3]]);
+        assert_eq!(faces(1), vec![vec![1, 2], vec![1, 3], vec![2, 3]]);
+        assert_eq!(faces(2), vec![vec![1], vec![2], vec![3]]);
+        assert!(faces(3).is_empty());
+
+        assert!(Simplex::new(vec![1, 3]).is_face_of(&simplex));
+        assert!(!Simplex::new(vec![0, 3]).is_face_of(&simplex));
+        assert_eq!(simplex.join(&Simplex::new(vec![0, 5])), Some(Simplex::new(vec![0, 1, 2, 3, 5])));
+        assert_eq!(simplex.join(&Simplex::new(vec![2])), None);
+    }
+
+    /// Two triangles 012 and 123 sharing the edge 12, and an edge 34.
+    fn bowtie() -> SimplicialComplex {
+        let simplices = [
+            vec![0],
+            vec![1],
+            vec![2],
+            vec![3],
+            vec![4],
+            vec![0, 1],
+            vec![0, 2],
+            vec![1, 2],
+            vec![1, 3],
+            vec![2, 3],
+            vec![3, 4],
+            vec![0, 1, 2],
+            vec![1, 2, 3],
+        ];
+        let levels = vec![0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 3.0, 2.0, 4.0];
+        SimplicialComplex::new(simplices.into_iter().map(Simplex::new).collect(), levels).unwrap()
+    }
+
+    #[test]
+    fn test_star_and_link() {
+        let complex = bowtie();
+        let vertices = |indices: Vec<usize>| -> Vec<Vec<usize>> {
+            indices.into_iter().map(|ix| complex.simplices[ix].vertices.clone()).collect()
+        };
+        let edge = Simplex::new(vec![1, 2]);
+        assert_eq!(vertices(complex.cofaces(&edge)), vec![vec![0, 1, 2], vec![1, 2, 3]]);
+        assert_eq!(
+            vertices(complex.star(&Simplex::new(vec![3]))),
+            vec![vec![3], vec![1, 3], vec![2, 3], vec![3, 4], vec![1, 2, 3]]
+        );
+        assert!(complex.star(&Simplex::new(vec![0, 4])).is_empty());
+        assert!(complex.cofaces(&Simplex::new(vec![7])).is_empty());
+        assert_eq!(vertices(complex.truncate(2.0).cofaces(&edge)), vec![vec![0, 1, 2]]);
+
+        let link = complex.link(&edge);
+        assert_eq!(link.simplices, vec![Simplex::new(vec![0]), Simplex::new(vec![3])]);
+        let link = complex.link(&Simplex::new(vec![3]));
+        assert_eq!(link.len(), 4);
+        assert!(link.index_of(&Simplex::new(vec![1, 2])).is_some());
+
+        let star = complex.closed_star(&Simplex::new(vec![4]));
+        assert_eq!(star.simplices, vec![Simplex::new(vec![3]), Simplex::new(vec![4]), Simplex::new(vec![3, 4])]);
+        assert_eq!(star.levels, vec![0.0, 0.0, 3.0]);
+        assert_eq!(complex.closed_star(&Simplex::new(vec![3])).len(), 9);
+    }
+
+    #[test]
+    fn test_skeleton_and_join() {
+        let complex = bowtie();
+        assert_eq!(complex.skeleton(0).len(), 5);
+        assert_eq!(complex.skeleton(1).len(), 11);
+        assert_eq!(complex.skeleton(2).len(), complex.len());
+
+        // The join of an edge with two points is a pair of triangles glued along the edge
+        let edge = SimplicialComplex::new(
+            vec![Simplex::new(vec![0]), Simplex::new(vec![1]), Simplex::new(vec![0, 1])],
+            vec![0.0, 0.0, 1.0],
+        )
+        .unwrap();
+        let points =
+            SimplicialComplex::new(vec![Simplex::new(vec![2]), Simplex::new(vec![3])], vec![0.5, 2.0]).unwrap();
+        let join = edge.join(&points).unwrap();
+        assert_eq!(join.len(), 3 + 2 + 6);
+        assert_eq!(join.levels[join.index_of(&Simplex::new(vec![0, 1, 3])).unwrap()], 2.0);
+        assert_eq!(join.levels[join.index_of(&Simplex::new(vec![0, 2])).unwrap()], 0.5);
+        let homology = join.integral_homology();
+        assert_eq!((0..3).map(|dim| homology[&dim].rank).collect::<Vec<_>>(), vec![1, 0, 0]);
+
+        assert!(matches!(edge.join(&edge), Err(SimplicialComplexError::SharedVertex(_))));
+    }
+
     #[test]
     fn test_simplicial_complex_creation() {
         let result = SimplicialComplex::new(vec![Simplex::new(vec![])], vec![0.0]);
@@ -184,6 +599,125 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_closure_validation() {
+        let result = SimplicialComplex::new(vec![Simplex::new(vec![0]), Simplex::new(vec![0])], vec![0.0, 0.0]);
+        assert!(matches!(result, Err(SimplicialComplexError::DuplicateSimplex(_))));
+
+        let result = SimplicialComplex::new(vec![Simplex::new(vec![0]), Simplex::new(vec![0, 1])], vec![0.0, 1.0]);
+        match result {
+            Err(SimplicialComplexError::MissingFace { simplex, face }) => {
+                assert_eq!((simplex, face), (vec![0, 1], vec![1]))
+            },
+            other => panic!("Expected a missing face, got {:?}", other),
+        }
+
+        let simplices = vec![Simplex::new(vec![0]), Simplex::new(vec![1]), Simplex::new(vec![0, 1])];
+        let result = SimplicialComplex::new(simplices, vec![0.0, 2.0, 1.0]);
+        assert!(matches!(
+            result,
+            Err(SimplicialComplexError::FaceAfterCoface { simplex_level: 1.0, face_level: 2.0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_filtration_order() {
+        // Faces listed after their cofaces at the same level still come first
+        let simplices = vec![
+            Simplex::new(vec![0, 1, 2]),
+            Simplex::new(vec![1, 2]),
+            Simplex::new(vec![0, 2]),
+            Simplex::new(vec![0, 1]),
+            Simplex::new(vec![2]),
+            Simplex::new(vec![1]),
+            Simplex::new(vec![0]),
+        ];
+        let levels = vec![1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+        let complex = SimplicialComplex::new(simplices.clone(), levels.clone()).unwrap();
+        let order: Vec<Vec<usize>> = complex.simplices.iter().map(|s| s.vertices.clone()).collect();
+        assert_eq!(order, vec![vec![0], vec![1], vec![2], vec![0, 1], vec![0, 2], vec![1, 2], vec![0, 1, 2]]);
+        for ix in 0..complex.len() {
+            assert!(complex.boundary(ix).iter().all(|&face| face < ix));
+        }
+
+        // The order does not depend on the input order
+        let reversed =
+            SimplicialComplex::new(simplices.iter().rev().cloned().collect(), levels.iter().rev().copied().collect());
+        assert_eq!(reversed.unwrap().simplices, complex.simplices);
+
+        // Ties can be broken differently, but never before faces
+        let complex =
+            SimplicialComplex::with_tie_breaker(simplices, levels, |a, b| b.vertices.cmp(&a.vertices)).unwrap();
+        let order: Vec<Vec<usize>> = complex.simplices.iter().map(|s| s.vertices.clone()).collect();
+        assert_eq!(order, vec![vec![2], vec![1], vec![0], vec![0, 1], vec![1, 2], vec![0, 2], vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn test_infinite_and_nan_levels() {
+        let simplices = vec![Simplex::new(vec![0]), Simplex::new(vec![1]), Simplex::new(vec![0, 1])];
+        let complex = SimplicialComplex::new(simplices.clone(), vec![0.0, f64::INFINITY, f64::INFINITY]).unwrap();
+        assert_eq!(complex.truncate(1e300).len(), 1);
+        let intervals = complex.persistence_intervals();
+        let mut pairs: Vec<(f64, f64)> = intervals[&0].iter().map(|i| (i.birth, i.death)).collect();
+        pairs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(pairs, vec![(0.0, f64::INFINITY), (f64::INFINITY, f64::INFINITY)]);
+
+        let result = SimplicialComplex::new(simplices.clone(), vec![0.0, f64::NAN, 1.0]);
+        assert!(matches!(result, Err(SimplicialComplexError::InvalidLevel { .. })));
+        let result = SimplicialComplex::closure(vec![Simplex::new(vec![0, 1])], vec![f64::NAN]);
+        assert!(matches!(result, Err(SimplicialComplexError::InvalidLevel { .. })));
+    }
+
+    #[test]
+    fn test_closure() {
+        // Missing faces get the smallest level of their cofaces
+        let simplices = vec![Simplex::new(vec![0, 1, 2]), Simplex::new(vec![1, 2, 3]), Simplex::new(vec![0])];
+        let complex = SimplicialComplex::closure(simplices, vec![2.0, 1.0, 0.5]).unwrap();
+        assert_eq!(complex.len(), 11);
+        let level = |vertices: Vec<usize>| complex.levels[complex.index_of(&Simplex::new(vertices)).unwrap()];
+        assert_eq!(level(vec![0]), 0.5);
+        assert_eq!(level(vec![1]), 1.0);
+        assert_eq!(level(vec![1, 2]), 1.0);
+        assert_eq!(level(vec![0, 1]), 2.0);
+        assert_eq!(level(vec![0, 1, 2]), 2.0);
+
+        // Faces always come before their cofaces
+        for ix in 0..complex.len() {
+            assert!(complex.boundary(ix).iter().all(|&face| face < ix));
+        }
+
+        // Given faces keep their level, and must not appear after their cofaces
+        let result = SimplicialComplex::closure(vec![Simplex::new(vec![0, 1]), Simplex::new(vec![0])], vec![1.0, 2.0]);
+        assert!(matches!(result, Err(SimplicialComplexError::FaceAfterCoface { .. })));
+    }
+
+    #[test]
+    fn test_signed_boundary() {
+        let simplices = vec![
+            Simplex::new(vec![0]),
+            Simplex::new(vec![1]),
+            Simplex::new(vec![2]),
+            Simplex::new(vec![0, 1]),
+            Simplex::new(vec![1, 2]),
+            Simplex::new(vec![0, 2]),
+            Simplex::new(vec![0, 1, 2]),
+        ];
+        let complex = SimplicialComplex::new(simplices, vec![0.0; 7]).unwrap();
+
+        let mut boundary = complex.signed_boundary(6);
+        boundary.sort();
+        assert_eq!(boundary, vec![(3, 1), (4, -1), (5, 1)]);
+
+        // The boundary of a boundary vanishes over the integers
+        let mut total: HashMap<usize, i64> = HashMap::new();
+        for (face, sign) in boundary {
+            for (subface, subsign) in complex.signed_boundary(face) {
+                *total.entry(subface).or_default() += sign * subsign;
+            }
+        }
+        assert!(total.values().all(|&c| c == 0));
+    }
+
     #[derive(Debug, PartialEq)]
     struct Interval {
         birth: f64,
@@ -237,11 +771,18 @@ mod tests {
         let complex = SimplicialComplex::new(simplices, levels).unwrap();
 
         // When
-        let all_intervals = complex.persistence_intervals();
-        debug!("Result {:?}", all_intervals);
+        let diagram = complex.persistence_diagram();
+        debug!("Result {:?}", diagram);
 
         // Then
-        // TODO make ordering-agnostic
+        assert_eq!(diagram.dimensions().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(
+            diagram.pairs(0).collect::<Vec<_>>(),
+            vec![(0.0, 1.0), (0.0, f64::INFINITY), (1.0, 1.0), (1.0, 2.0)]
+        );
+        assert_eq!(diagram.pairs(1).collect::<Vec<_>>(), vec![(2.0, 5.0), (3.0, 4.0)]);
+        assert!(diagram.intervals(2).is_empty());
+
         let expected: HashMap<usize, Vec<Interval>> = HashMap::from([
             (
                 0,
@@ -252,6 +793,12 @@ mod tests {
                         death: 1.0,
                         death_chain: HashSet::from([vec![0, 1]]),
                     },
+                    Interval {
+                        birth: 0.0,
+                        birth_chain: HashSet::from([vec![0]]),
+                        death: f64::INFINITY,
+                        death_chain: HashSet::new(),
+                    },
                     Interval {
                         birth: 1.0,
                         birth_chain: HashSet::from([vec![2]]),
@@ -262,60 +809,43 @@ mod tests {
                         birth: 1.0,
                         birth_chain: HashSet::from([vec![3]]),
                         death: 2.0,
-                        death_chain: HashSet::from([vec![2, 3]]),
-                    },
-                    Interval {
-                        birth: 0.0,
-                        birth_chain: HashSet::from([vec![0]]),
-                        death: f64::INFINITY,
-                        death_chain: HashSet::new(),
+                        death_chain: HashSet::from([vec![0, 3]]),
                     },
                 ],
             ),
             (
                 1,
                 vec![
-                    Interval {
-                        birth: 3.0,
-                        birth_chain: HashSet::from([vec![0, 2], vec![0, 1], vec![1, 2]]),
-                        death: 4.0,
-                        death_chain: HashSet::from([vec![0, 1, 2]]),
-                    },
                     Interval {
                         birth: 2.0,
                         birth_chain: HashSet::from([vec![0, 3], vec![0, 1], vec![1, 2], vec![2, 3]]),
                         death: 5.0,
                         death_chain: HashSet::from([vec![0, 1, 2], vec![0, 2, 3]]),
                     },
+                    Interval {
+                        birth: 3.0,
+                        birth_chain: HashSet::from([vec![0, 2], vec![0, 1], vec![1, 2]]),
+                        death: 4.0,
+                        death_chain: HashSet::from([vec![0, 1, 2]]),
+                    },
                 ],
             ),
-            (2, vec![]),
         ]);
-        for (dim, intervals) in &all_intervals {
-            debug!("=== {:?} ===", dim);
-            let mut intervals_dim = Vec::new();
-            for interval in intervals {
-                let birth_chains: HashSet<Vec<usize>> =
-                    interval.birth_chain.iter().map(|c| complex.chain(*c).vertices.clone()).collect();
-
-                let death_chains: HashSet<Vec<usize>>;
-                if let Some(death_chain) = &interval.death_chain {
-                    death_chains = death_chain.iter().map(|c| complex.chain(*c).vertices.clone()).collect();
-                } else {
-                    death_chains = HashSet::new();
-                }
-                debug!(
-                    "Birth: {:?} (level {:?} --- Death: {:?} (level {:?})",
-                    birth_chains, interval.birth, death_chains, interval.death
-                );
-                intervals_dim.push(Interval {
+        let vertices = |chain: &HashSet<usize>| -> HashSet<Vec<usize>> {
+            chain.iter().map(|&c| complex.chain(c).vertices.clone()).collect()
+        };
+        for (dim, expected) in &expected {
+            let intervals: Vec<Interval> = diagram
+                .intervals(*dim)
+                .iter()
+                .map(|interval| Interval {
                     birth: interval.birth,
-                    birth_chain: birth_chains,
+                    birth_chain: vertices(&interval.birth_chain),
                     death: interval.death,
-                    death_chain: death_chains,
-                });
-            }
-            assert_eq!(intervals_dim, expected[dim]);
+                    death_chain: interval.death_chain.as_ref().map(vertices).unwrap_or_default(),
+                })
+                .collect();
+            assert_eq!(&intervals, expected);
         }
     }
 }
@@ -0,0 +1,535 @@
+use ordered_float::OrderedFloat;
+use std::collections::HashSet;
+use thiserror::Error;
+
+use super::homology::{Chain, ChainComplex};
+
+/// Error types for sparse boundary matrices
+#[derive(Error, Debug)]
+pub enum SparseMatrixError {
+    #[error("Expected {expected} {name}, got {actual}")]
+    LengthMismatch { name: &'static str, expected: usize, actual: usize },
+    #[error("Column pointers must start at 0, be non-decreasing and end at the number of entries")]
+    InvalidPointers,
+    #[error("Row index {row} of column {column} is out of bounds or not before the column")]
+    InvalidRow { row: usize, column: usize },
+    #[error("Column index {column} of an entry is out of bounds for {n_columns} columns")]
+    InvalidColumn { column: usize, n_columns: usize },
+    #[error("Row {row} of dimension {row_dim} is in the boundary of column {column} of dimension {column_dim}")]
+    InvalidDimension { row: usize, row_dim: usize, column: usize, column_dim: usize },
+    #[error("Invalid filtration level {level} at column {column}")]
+    InvalidLevel { level: f64, column: usize },
+    #[error("Row {row} appears at level {row_level}, after column {column} at level {column_level}")]
+    FaceAfterCoface { row: usize, row_level: f64, column: usize, column_level: f64 },
+}
+
+/// A cell of a chain complex given by its boundary matrix, only known by its dimension.
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+pub struct Cell {
+    /// Dimension of the cell
+    pub dim: usize,
+}
+
+impl Chain for Cell {
+    fn dim(&self) -> usize {
+        self.dim
+    }
+}
+
+/// A filtered boundary matrix in compressed sparse column (CSC) form, with the dimension and
+/// filtration level of each column.
+///
+/// The matrix is square, with rows and columns indexed by the chains of the complex in
+/// filtration order. Entry (i, j) is the coefficient of chain i in the oriented boundary of
+/// chain j, and the entries of column j are stored at positions `col_ptr[j]..col_ptr[j + 1]`
+/// of `row_indices` and `values`, sorted by row.
+///
+/// It implements [`ChainComplex`], so that boundary matrices produced elsewhere can be fed
+/// straight into the persistence computation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseBoundaryMatrix {
+    col_ptr: Vec<usize>,
+    row_indices: Vec<usize>,
+    values: Vec<i64>,
+    cells: Vec<Cell>,
+    levels: Vec<f64>,
+}
+
+impl SparseBoundaryMatrix {
+    /// Creates a boundary matrix from its compressed sparse column representation.
+    ///
+    /// Entries within a column are sorted by row, and entries with the same row are summed.
+    ///
+    /// # Arguments
+    ///
+    /// * `col_ptr` - Start of each column in the entries, followed by the number of entries
+    /// * `row_indices` - Row of each entry
+    /// * `values` - Coefficient of each entry
+    /// * `dims` - Dimension of each column
+    /// * `levels` - Filtration level of each column
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the new matrix or an error if:
+    /// - The lengths of the arguments are inconsistent
+    /// - The column pointers are not valid
+    /// - A boundary entry is not in an earlier column of one dimension lower
+    /// - The filtration levels are NaN or decreasing
+    pub fn from_csc(
+        col_ptr: Vec<usize>,
+        row_indices: Vec<usize>,
+        values: Vec<i64>,
+        dims: Vec<usize>,
+        levels: Vec<f64>,
+    ) -> Result<Self, SparseMatrixError> {
+        let n = dims.len();
+        if col_ptr.len() != n + 1 {
+            return Err(SparseMatrixError::LengthMismatch {
+                name: "column pointers",
+                expected: n + 1,
+                actual: col_ptr.len(),
+            });
+        }
+        if values.len() != row_indices.len() {
+            return Err(SparseMatrixError::LengthMismatch {
+                name: "values",
+                expected: row_indices.len(),
+                actual: values.len(),
+            });
+        }
+        if col_ptr[0] != 0 || col_ptr[n] != row_indices.len() || col_ptr.windows(2).any(|w| w[0] > w[1]) {
+            return Err(SparseMatrixError::InvalidPointers);
+        }
+
+        let columns: Vec<Vec<(usize, i64)>> =
+            (0..n).map(|jx| (col_ptr[jx]..col_ptr[jx + 1]).map(|kx| (row_indices[kx], values[kx])).collect()).collect();
+        Self::from_columns(columns, dims, levels)
+    }
+
+    /// Creates a boundary matrix from its coordinate (COO) representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - Row of each entry
+    /// * `columns` - Column of each entry
+    /// * `values` - Coefficient of each entry
+    /// * `dims` - Dimension of each column
+    /// * `levels` - Filtration level of each column
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the new matrix or an error, as for [`Self::from_csc`].
+    pub fn from_coo(
+        rows: &[usize],
+        columns: &[usize],
+        values: &[i64],
+        dims: Vec<usize>,
+        levels: Vec<f64>,
+    ) -> Result<Self, SparseMatrixError> {
+        if columns.len() != rows.len() {
+            return Err(SparseMatrixError::LengthMismatch {
+                name: "column indices",
+                expected: rows.len(),
+                actual: columns.len(),
+            });
+        }
+        if values.len() != rows.len() {
+            return Err(SparseMatrixError::LengthMismatch {
+                name: "values",
+                expected: rows.len(),
+                actual: values.len(),
+            });
+        }
+        let mut entries: Vec<Vec<(usize, i64)>> = vec![Vec::new(); dims.len()];
+        for ((&row, &column), &value) in rows.iter().zip(columns).zip(values) {
+            if column >= dims.len() {
+                return Err(SparseMatrixError::InvalidColumn { column, n_columns: dims.len() });
+            }
+            entries[column].push((row, value));
+        }
+        Self::from_columns(entries, dims, levels)
+    }
+
+    /// Creates a boundary matrix from columns given in any order, by sorting them into
+    /// filtration order: by level, then dimension, then original index.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - Oriented boundary of each column, as (row, coefficient) pairs indexing the
+    ///   columns as given
+    /// * `dims` - Dimension of each column
+    /// * `levels` - Filtration level of each column
+    ///
+    /// # Returns
+    ///
+    /// A Result containing either the sorted matrix and the original index of each of its
+    /// columns, or an error if:
+    /// - The lengths of the arguments are inconsistent
+    /// - A row is out of bounds, refers to its own column or is not of dimension one lower
+    /// - A level is NaN, or a row appears after its column
+    pub fn from_unordered_columns(
+        columns: Vec<Vec<(usize, i64)>>,
+        dims: Vec<usize>,
+        levels: Vec<f64>,
+    ) -> Result<(Self, Vec<usize>), SparseMatrixError> {
+        let n = dims.len();
+        if columns.len() != n {
+            return Err(SparseMatrixError::LengthMismatch { name: "columns", expected: n, actual: columns.len() });
+        }
+        if levels.len() != n {
+            return Err(SparseMatrixError::LengthMismatch {
+                name: "filtration levels",
+                expected: n,
+                actual: levels.len(),
+            });
+        }
+        if let Some(column) = levels.iter().position(|level| level.is_nan()) {
+            return Err(SparseMatrixError::InvalidLevel { level: levels[column], column });
+        }
+        for (column, entries) in columns.iter().enumerate() {
+            for &(row, _) in entries {
+                if row >= n || row == column {
+                    return Err(SparseMatrixError::InvalidRow { row, column });
+                }
+                if dims[row] + 1 != dims[column] {
+                    return Err(SparseMatrixError::InvalidDimension {
+                        row,
+                        row_dim: dims[row],
+                        column,
+                        column_dim: dims[column],
+                    });
+                }
+                if levels[row] > levels[column] {
+                    return Err(SparseMatrixError::FaceAfterCoface {
+                        row,
+                        row_level: levels[row],
+                        column,
+                        column_level: levels[column],
+                    });
+                }
+            }
+        }
+
+        // Faces have lower dimension and no higher level, so they come before their cofaces
+        let mut original_indices: Vec<usize> = (0..n).collect();
+        original_indices.sort_by_key(|&ix| (OrderedFloat(levels[ix]), dims[ix], ix));
+        let mut new_indices = vec![0; n];
+        for (new, &original) in original_indices.iter().enumerate() {
+            new_indices[original] = new;
+        }
+
+        let sorted_columns = original_indices
+            .iter()
+            .map(|&original| columns[original].iter().map(|&(row, value)| (new_indices[row], value)).collect())
+            .collect();
+        let sorted_dims = original_indices.iter().map(|&ix| dims[ix]).collect();
+        let sorted_levels = original_indices.iter().map(|&ix| levels[ix]).collect();
+        let matrix = Self::from_columns(sorted_columns, sorted_dims, sorted_levels)?;
+        Ok((matrix, original_indices))
+    }
+
+    /// Exports the oriented boundary matrix of a chain complex, assumed to be in filtration order.
+    pub(crate) fn from_complex<T: Chain + std::fmt::Debug, C: ChainComplex<T> + ?Sized>(complex: &C) -> Self {
+        let mut col_ptr = Vec::with_capacity(complex.len() + 1);
+        let mut row_indices = Vec::new();
+        let mut values = Vec::new();
+        col_ptr.push(0);
+        for ix in 0..complex.len() {
+            let mut entries = complex.signed_boundary(ix);
+            entries.sort_unstable();
+            for (row, value) in entries {
+                row_indices.push(row);
+                values.push(value);
+            }
+            col_ptr.push(row_indices.len());
+        }
+
+        let cells = complex.chains().iter().map(|c| Cell { dim: c.dim() }).collect();
+        let levels = (0..complex.len()).map(|ix| complex.filtration_level(ix).into_inner()).collect();
+        Self { col_ptr, row_indices, values, cells, levels }
+    }
+
+    /// Sorts and validates the columns and compresses them.
+    fn from_columns(
+        columns: Vec<Vec<(usize, i64)>>,
+        dims: Vec<usize>,
+        levels: Vec<f64>,
+    ) -> Result<Self, SparseMatrixError> {
+        let n = dims.len();
+        if levels.len() != n {
+            return Err(SparseMatrixError::LengthMismatch {
+                name: "filtration levels",
+                expected: n,
+                actual: levels.len(),
+            });
+        }
+        for (column, &level) in levels.iter().enumerate() {
+            if level.is_nan() || (column > 0 && level < levels[column - 1]) {
+                return Err(SparseMatrixError::InvalidLevel { level, column });
+            }
+        }
+
+        let mut col_ptr = Vec::with_capacity(n + 1);
+        let mut row_indices = Vec::new();
+        let mut values = Vec::new();
+        col_ptr.push(0);
+        for (column, mut entries) in columns.into_iter().enumerate() {
+            entries.sort_unstable_by_key(|&(row, _)| row);
+            for (row, value) in entries {
+                if row >= column {
+                    return Err(SparseMatrixError::InvalidRow { row, column });
+                }
+                if dims[row] + 1 != dims[column] {
+                    return Err(SparseMatrixError::InvalidDimension {
+                        row,
+                        row_dim: dims[row],
+                        column,
+                        column_dim: dims[column],
+                    });
+                }
+                // Merge duplicate entries
+                if row_indices.len() > col_ptr[column] && row_indices.last() == Some(&row) {
+                    *values.last_mut().unwrap() += value;
+                } else {
+                    row_indices.push(row);
+                    values.push(value);
+                }
+            }
+            col_ptr.push(row_indices.len());
+        }
+
+        let cells = dims.into_iter().map(|dim| Cell { dim }).collect();
+        Ok(Self { col_ptr, row_indices, values, cells, levels })
+    }
+
+    /// Returns the number of columns (and rows) of the matrix.
+    pub fn n_columns(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns the number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.row_indices.len()
+    }
+
+    /// Returns the start of each column in the entries, followed by the number of entries.
+    pub fn col_ptr(&self) -> &[usize] {
+        &self.col_ptr
+    }
+
+    /// Returns the row of each entry.
+    pub fn row_indices(&self) -> &[usize] {
+        &self.row_indices
+    }
+
+    /// Returns the coefficient of each entry.
+    pub fn values(&self) -> &[i64] {
+        &self.values
+    }
+
+    /// Returns the dimension of each column.
+    pub fn dims(&self) -> Vec<usize> {
+        self.cells.iter().map(|c| c.dim).collect()
+    }
+
+    /// Returns the filtration level of each column.
+    pub fn levels(&self) -> &[f64] {
+        &self.levels
+    }
+
+    /// Returns the rows and coefficients of the entries of a column.
+    pub fn column(&self, index: usize) -> (&[usize], &[i64]) {
+        let range = self.col_ptr[index]..self.col_ptr[index + 1];
+        (&self.row_indices[range.clone()], &self.values[range])
+    }
+
+    /// Converts the matrix to coordinate (COO) form.
+    ///
+    /// # Returns
+    ///
+    /// The rows, columns and values of the entries, ordered by column then row.
+    pub fn to_coo(&self) -> (Vec<usize>, Vec<usize>, Vec<i64>) {
+        let columns =
+            (0..self.n_columns()).flat_map(|jx| std::iter::repeat_n(jx, self.col_ptr[jx + 1] - self.col_ptr[jx]));
+        (self.row_indices.clone(), columns.collect(), self.values.clone())
+    }
+
+    /// Returns the coboundary matrix, i.e. the transpose of the boundary matrix, in compressed
+    /// sparse column form.
+    ///
+    /// Column j of the result holds the cofaces of chain j. Since the result is not a boundary
+    /// matrix, it is returned as raw (col_ptr, row_indices, values) arrays; the dimensions and
+    /// levels are those of the boundary matrix.
+    pub fn coboundary(&self) -> (Vec<usize>, Vec<usize>, Vec<i64>) {
+        let n = self.n_columns();
+        let mut col_ptr = vec![0; n + 1];
+        for &row in &self.row_indices {
+            col_ptr[row + 1] += 1;
+        }
+        for ix in 0..n {
+            col_ptr[ix + 1] += col_ptr[ix];
+        }
+
+        // Columns are visited in order, so the rows of the transpose end up sorted
+        let mut next = col_ptr.clone();
+        let mut row_indices = vec![0; self.nnz()];
+        let mut values = vec![0; self.nnz()];
+        for jx in 0..n {
+            let (rows, coefficients) = self.column(jx);
+            for (&row, &value) in rows.iter().zip(coefficients) {
+                row_indices[next[row]] = jx;
+                values[next[row]] = value;
+                next[row] += 1;
+            }
+        }
+        (col_ptr, row_indices, values)
+    }
+}
+
+impl ChainComplex<Cell> for SparseBoundaryMatrix {
+    fn chain(&self, index: usize) -> &Cell {
+        &self.cells[index]
+    }
+
+    fn chains(&self) -> &Vec<Cell> {
+        &self.cells
+    }
+
+    fn boundary(&self, index: usize) -> HashSet<usize> {
+        let (rows, values) = self.column(index);
+        rows.iter().zip(values).filter(|(_, &value)| value % 2 != 0).map(|(&row, _)| row).collect()
+    }
+
+    fn signed_boundary(&self, index: usize) -> Vec<(usize, i64)> {
+        let (rows, values) = self.column(index);
+        rows.iter().copied().zip(values.iter().copied()).filter(|&(_, value)| value != 0).collect()
+    }
+
+    fn filtration_level(&self, index: usize) -> OrderedFloat<f64> {
+        OrderedFloat(self.levels[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::PrimeField;
+    use crate::homology::PersistenceOptions;
+    use crate::simplicial_complex::{Simplex, SimplicialComplex};
+
+    fn hollow_triangle() -> SimplicialComplex {
+        let simplices = vec![
+            Simplex::new(vec![0]),
+            Simplex::new(vec![1]),
+            Simplex::new(vec![2]),
+            Simplex::new(vec![0, 1]),
+            Simplex::new(vec![1, 2]),
+            Simplex::new(vec![0, 2]),
+            Simplex::new(vec![0, 1, 2]),
+        ];
+        SimplicialComplex::new(simplices, vec![0.0, 0.0, 1.0, 1.0, 2.0, 3.0, 4.0]).unwrap()
+    }
+
+    #[test]
+    fn test_export() {
+        let complex = hollow_triangle();
+        let matrix = complex.sparse_boundary_matrix();
+        assert_eq!(matrix.col_ptr(), &[0, 0, 0, 0, 2, 4, 6, 9]);
+        assert_eq!(matrix.row_indices(), &[0, 1, 1, 2, 0, 2, 3, 4, 5]);
+        assert_eq!(matrix.values(), &[-1, 1, -1, 1, -1, 1, 1, 1, -1]);
+        assert_eq!(matrix.dims(), vec![0, 0, 0, 1, 1, 1, 2]);
+        assert_eq!(matrix.levels(), complex.levels.as_slice());
+
+        // The sparse matrix agrees with the dense one
+        let dense = complex.boundary_matrix();
+        let (rows, columns, _) = matrix.to_coo();
+        assert_eq!(rows.len(), dense.sum());
+        assert!(rows.iter().zip(&columns).all(|(&i, &j)| dense[[i, j]] == 1));
+
+        let (col_ptr, row_indices, values) = matrix.coboundary();
+        assert_eq!(col_ptr, vec![0, 2, 4, 6, 7, 8, 9, 9]);
+        assert_eq!(row_indices, vec![3, 5, 3, 4, 4, 5, 6, 6, 6]);
+        assert_eq!(values, vec![-1, -1, 1, -1, 1, 1, 1, 1, -1]);
+    }
+
+    #[test]
+    fn test_persistence_from_sparse() {
+        let complex = hollow_triangle();
+        let (rows, columns, values) = complex.sparse_boundary_matrix().to_coo();
+        let dims = complex.sparse_boundary_matrix().dims();
+        let matrix = SparseBoundaryMatrix::from_coo(&rows, &columns, &values, dims, complex.levels.clone()).unwrap();
+        assert_eq!(matrix, complex.sparse_boundary_matrix());
+
+        for field in [PrimeField::new(2).unwrap(), PrimeField::new(3).unwrap()] {
+            let options = PersistenceOptions { field, ..Default::default() };
+            assert_eq!(
+                matrix.persistence_intervals_with_options(&options),
+                complex.persistence_intervals_with_options(&options)
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_matrices() {
+        // Pointers out of range
+        let result = SparseBoundaryMatrix::from_csc(vec![0, 0, 3], vec![0], vec![1], vec![0, 1], vec![0.0, 0.0]);
+        assert!(matches!(result, Err(SparseMatrixError::InvalidPointers)));
+
+        // Entry after its column
+        let result = SparseBoundaryMatrix::from_coo(&[1], &[0], &[1], vec![1, 0], vec![0.0, 0.0]);
+        assert!(matches!(result, Err(SparseMatrixError::InvalidRow { row: 1, column: 0 })));
+
+        // Edge in the boundary of an edge
+        let result = SparseBoundaryMatrix::from_coo(&[0], &[1], &[1], vec![1, 1], vec![0.0, 0.0]);
+        assert!(matches!(result, Err(SparseMatrixError::InvalidDimension { .. })));
+
+        // Levels out of order or NaN
+        let result = SparseBoundaryMatrix::from_coo(&[], &[], &[], vec![0, 0], vec![1.0, 0.0]);
+        assert!(matches!(result, Err(SparseMatrixError::InvalidLevel { column: 1, .. })));
+        let result = SparseBoundaryMatrix::from_coo(&[], &[], &[], vec![0], vec![f64::NAN]);
+        assert!(matches!(result, Err(SparseMatrixError::InvalidLevel { column: 0, .. })));
+
+        let result = SparseBoundaryMatrix::from_coo(&[], &[], &[], vec![0], vec![]);
+        assert!(matches!(result, Err(SparseMatrixError::LengthMismatch { .. })));
+
+        // Entries out of bounds or with inconsistent lengths
+        let result = SparseBoundaryMatrix::from_coo(&[0], &[2], &[1], vec![0, 1], vec![0.0, 0.0]);
+        assert!(matches!(result, Err(SparseMatrixError::InvalidColumn { column: 2, n_columns: 2 })));
+        let result = SparseBoundaryMatrix::from_coo(&[0], &[1, 1], &[1], vec![0, 1], vec![0.0, 0.0]);
+        assert!(matches!(
+            result,
+            Err(SparseMatrixError::LengthMismatch { name: "column indices", expected: 1, actual: 2 })
+        ));
+        let result = SparseBoundaryMatrix::from_coo(&[0], &[1], &[], vec![0, 1], vec![0.0, 0.0]);
+        assert!(matches!(result, Err(SparseMatrixError::LengthMismatch { name: "values", expected: 1, actual: 0 })));
+    }
+
+    #[test]
+    fn test_unordered_columns() {
+        // The hollow triangle with its columns shuffled, given[k] being the column at position k
+        let expected = hollow_triangle().sparse_boundary_matrix();
+        let given = [6, 3, 0, 5, 1, 4, 2];
+        let mut position = [0; 7];
+        for (k, &jx) in given.iter().enumerate() {
+            position[jx] = k;
+        }
+        let columns = given
+            .iter()
+            .map(|&jx| {
+                let (rows, values) = expected.column(jx);
+                rows.iter().map(|&row| position[row]).zip(values.iter().copied()).collect()
+            })
+            .collect();
+        let dims = given.iter().map(|&jx| expected.dims()[jx]).collect();
+        let levels = given.iter().map(|&jx| expected.levels()[jx]).collect();
+        let (matrix, original_indices) = SparseBoundaryMatrix::from_unordered_columns(columns, dims, levels).unwrap();
+        assert_eq!(matrix, expected);
+        assert_eq!(original_indices, position.to_vec());
+
+        let result =
+            SparseBoundaryMatrix::from_unordered_columns(vec![vec![], vec![(0, 1)]], vec![0, 1], vec![1.0, 0.0]);
+        assert!(matches!(result, Err(SparseMatrixError::FaceAfterCoface { row: 0, column: 1, .. })));
+        let result = SparseBoundaryMatrix::from_unordered_columns(vec![vec![], vec![(2, 1)]], vec![0, 1], vec![0.0; 2]);
+        assert!(matches!(result, Err(SparseMatrixError::InvalidRow { row: 2, column: 1 })));
+    }
+}
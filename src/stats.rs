@@ -0,0 +1,215 @@
+use ordered_float::OrderedFloat;
+use std::collections::HashMap;
+
+use super::diagram::PersistenceDiagram;
+use super::distance::{bottleneck_distance, wasserstein_distance, WassersteinOptions};
+use super::homology::PersistenceInterval;
+
+/// Returns the lifetimes (death - birth) of the finite intervals.
+fn finite_lifetimes(intervals: &[PersistenceInterval]) -> impl Iterator<Item = f64> + '_ {
+    intervals.iter().filter(|i| i.death.is_finite()).map(|i| i.death - i.birth)
+}
+
+/// Computes the persistent entropy of a set of intervals, i.e. the Shannon entropy of the
+/// lifetimes normalized to a probability distribution.
+///
+/// Infinite intervals are ignored, so they should be truncated first (see
+/// [`PersistenceDiagram::truncate_infinite`]) if they are to contribute.
+///
+/// # Returns
+///
+/// The entropy (natural logarithm), which is 0 if there are no intervals with positive lifetime.
+pub fn persistent_entropy(intervals: &[PersistenceInterval]) -> f64 {
+    let total: f64 = finite_lifetimes(intervals).sum();
+    finite_lifetimes(intervals)
+        .filter(|&l| l > 0.0)
+        .map(|l| {
+            let p = l / total;
+            -p * p.ln()
+        })
+        .sum()
+}
+
+/// Computes the total p-persistence of a set of intervals, i.e. the sum of the p-th powers of
+/// the lifetimes of the finite intervals.
+///
+/// # Arguments
+///
+/// * `intervals` - Intervals of a single dimension
+/// * `p` - Exponent applied to each lifetime
+pub fn total_persistence(intervals: &[PersistenceInterval], p: f64) -> f64 {
+    finite_lifetimes(intervals).map(|l| l.powf(p)).sum()
+}
+
+/// Distance used to measure the amplitude of a diagram, i.e. its distance to the empty diagram.
+#[derive(Clone, Debug, Default)]
+pub enum Amplitude {
+    /// Bottleneck distance
+    #[default]
+    Bottleneck,
+    /// Wasserstein distance with the given options
+    Wasserstein(WassersteinOptions),
+}
+
+/// Computes the amplitude of a set of intervals, i.e. its distance to the empty diagram.
+///
+/// # Returns
+///
+/// The amplitude, which is infinite if there are infinite intervals.
+pub fn amplitude(intervals: &[PersistenceInterval], kind: &Amplitude) -> f64 {
+    match kind {
+        Amplitude::Bottleneck => bottleneck_distance(intervals, &[]),
+        Amplitude::Wasserstein(options) => wasserstein_distance(intervals, &[], options),
+    }
+}
+
+/// Counts the intervals whose lifetime is strictly greater than the given value, including
+/// infinite intervals.
+pub fn count_above(intervals: &[PersistenceInterval], lifetime: f64) -> usize {
+    intervals.iter().filter(|i| i.death - i.birth > lifetime).count()
+}
+
+/// Computes quantiles of the lifetimes of the finite intervals, interpolating linearly between
+/// order statistics.
+///
+/// # Arguments
+///
+/// * `intervals` - Intervals of a single dimension
+/// * `quantiles` - Quantiles to compute, between 0 and 1
+///
+/// # Returns
+///
+/// The lifetime at each quantile (NaN if there are no finite intervals).
+pub fn lifetime_quantiles(intervals: &[PersistenceInterval], quantiles: &[f64]) -> Vec<f64> {
+    let mut lifetimes: Vec<f64> = finite_lifetimes(intervals).collect();
+    lifetimes.sort_by_key(|&l| OrderedFloat(l));
+    if lifetimes.is_empty() {
+        return vec![f64::NAN; quantiles.len()];
+    }
+
+    quantiles
+        .iter()
+        .map(|&q| {
+            let position = q.clamp(0.0, 1.0) * (lifetimes.len() - 1) as f64;
+            let (lo, hi) = (position.floor() as usize, position.ceil() as usize);
+            lifetimes[lo] + (lifetimes[hi] - lifetimes[lo]) * (position - lo as f64)
+        })
+        .collect()
+}
+
+/// Options controlling the summary statistics computed by [`diagram_statistics`].
+#[derive(Clone, Debug)]
+pub struct StatisticsOptions {
+    /// Exponent of the total persistence
+    pub p: f64,
+    /// Distance used for the amplitude
+    pub amplitude: Amplitude,
+    /// Lifetime above which intervals are counted
+    pub lifetime: f64,
+    /// Lifetime quantiles to compute
+    pub quantiles: Vec<f64>,
+}
+
+impl Default for StatisticsOptions {
+    fn default() -> Self {
+        Self { p: 1.0, amplitude: Amplitude::default(), lifetime: 0.0, quantiles: vec![0.25, 0.5, 0.75] }
+    }
+}
+
+/// Summary statistics of the intervals of a single dimension.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    /// Persistent entropy of the finite intervals
+    pub entropy: f64,
+    /// Total p-persistence of the finite intervals
+    pub total_persistence: f64,
+    /// Distance to the empty diagram
+    pub amplitude: f64,
+    /// Number of intervals with lifetime above the threshold
+    pub count_above: usize,
+    /// Lifetime quantiles of the finite intervals
+    pub quantiles: Vec<f64>,
+}
+
+/// Computes summary statistics of a persistence diagram in every dimension.
+///
+/// # Arguments
+///
+/// * `diagram` - Persistence diagram to summarize
+/// * `options` - Options controlling the statistics
+///
+/// # Returns
+///
+/// A HashMap where keys are dimensions and values are the statistics in that dimension.
+pub fn diagram_statistics(diagram: &PersistenceDiagram, options: &StatisticsOptions) -> HashMap<usize, Statistics> {
+    diagram
+        .dimensions()
+        .map(|dim| {
+            let intervals = diagram.intervals(dim);
+            let statistics = Statistics {
+                entropy: persistent_entropy(intervals),
+                total_persistence: total_persistence(intervals, options.p),
+                amplitude: amplitude(intervals, &options.amplitude),
+                count_above: count_above(intervals, options.lifetime),
+                quantiles: lifetime_quantiles(intervals, &options.quantiles),
+            };
+            (dim, statistics)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::GroundMetric;
+    use crate::test_utils::intervals;
+
+    #[test]
+    fn test_entropy_and_total_persistence() {
+        let equal = intervals(&[(0.0, 1.0), (1.0, 2.0), (0.5, 1.5), (2.0, 3.0)]);
+        assert!((persistent_entropy(&equal) - 4.0_f64.ln()).abs() < 1e-12);
+        assert_eq!(persistent_entropy(&intervals(&[(0.0, 2.0), (1.0, f64::INFINITY)])), 0.0);
+        assert_eq!(persistent_entropy(&[]), 0.0);
+
+        let pairs = intervals(&[(0.0, 1.0), (0.0, 3.0), (1.0, f64::INFINITY)]);
+        assert_eq!(total_persistence(&pairs, 1.0), 4.0);
+        assert_eq!(total_persistence(&pairs, 2.0), 10.0);
+    }
+
+    #[test]
+    fn test_amplitude_and_counts() {
+        let pairs = intervals(&[(0.0, 1.0), (0.0, 3.0), (1.0, 1.5)]);
+        assert_eq!(amplitude(&pairs, &Amplitude::Bottleneck), 1.5);
+        let options = WassersteinOptions { metric: GroundMetric::LInf, exponent: 1.0, ..Default::default() };
+        assert_eq!(amplitude(&pairs, &Amplitude::Wasserstein(options)), 2.25);
+        assert_eq!(amplitude(&intervals(&[(0.0, f64::INFINITY)]), &Amplitude::Bottleneck), f64::INFINITY);
+
+        assert_eq!(count_above(&pairs, 0.5), 2);
+        assert_eq!(count_above(&pairs, 1.0), 1);
+        assert_eq!(count_above(&intervals(&[(0.0, f64::INFINITY)]), 1.0), 1);
+    }
+
+    #[test]
+    fn test_quantiles() {
+        let pairs = intervals(&[(0.0, 4.0), (0.0, 1.0), (0.0, 3.0), (0.0, 2.0), (0.0, f64::INFINITY)]);
+        assert_eq!(lifetime_quantiles(&pairs, &[0.0, 0.5, 1.0, 0.25]), vec![1.0, 2.5, 4.0, 1.75]);
+        assert!(lifetime_quantiles(&[], &[0.5])[0].is_nan());
+    }
+
+    #[test]
+    fn test_diagram_statistics() {
+        let diagram = PersistenceDiagram::new(HashMap::from([
+            (0, intervals(&[(0.0, 1.0), (0.0, f64::INFINITY)])),
+            (1, intervals(&[(1.0, 2.0), (1.5, 2.0)])),
+        ]));
+        let statistics = diagram_statistics(&diagram, &StatisticsOptions::default());
+        assert_eq!(statistics[&0].amplitude, f64::INFINITY);
+        assert_eq!(statistics[&0].count_above, 2);
+        let expected_entropy = -(2.0 / 3.0_f64) * (2.0 / 3.0_f64).ln() - (1.0 / 3.0_f64) * (1.0 / 3.0_f64).ln();
+        assert!((statistics[&1].entropy - expected_entropy).abs() < 1e-12);
+        assert_eq!(statistics[&1].total_persistence, 1.5);
+        assert_eq!(statistics[&1].amplitude, 0.5);
+        assert_eq!(statistics[&1].count_above, 2);
+        assert_eq!(statistics[&1].quantiles, vec![0.625, 0.75, 0.875]);
+    }
+}
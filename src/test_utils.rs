@@ -0,0 +1,48 @@
+use ndarray::Array2;
+use std::collections::{HashMap, HashSet};
+
+use super::homology::PersistenceInterval;
+use super::point_cloud::PointCloud;
+
+/// Creates an interval without representatives.
+pub(crate) fn interval(birth: f64, death: f64) -> PersistenceInterval {
+    PersistenceInterval { birth, birth_chain: HashSet::new(), death, death_chain: None, coefficients: HashMap::new() }
+}
+
+/// Creates intervals without representatives from (birth, death) pairs.
+pub(crate) fn intervals(pairs: &[(f64, f64)]) -> Vec<PersistenceInterval> {
+    pairs.iter().map(|&(birth, death)| interval(birth, death)).collect()
+}
+
+/// Small deterministic pseudo-random numbers in [0, 1), from a linear congruential generator.
+pub(crate) fn random_numbers(n: usize, seed: u64) -> Vec<f64> {
+    let mut state = seed;
+    (0..n)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        })
+        .collect()
+}
+
+/// Points evenly spaced in angle around the unit circle, with their radii perturbed by up to
+/// `noise` (pseudo-randomly, but deterministically).
+pub(crate) fn circle_cloud(n_points: usize, noise: f64) -> PointCloud {
+    let radii = random_numbers(n_points, n_points as u64);
+    let points = Array2::from_shape_fn((n_points, 2), |(ix, jx)| {
+        let angle = 2.0 * std::f64::consts::PI * ix as f64 / n_points as f64;
+        let radius = 1.0 + noise * radii[ix];
+        if jx == 0 {
+            radius * angle.cos()
+        } else {
+            radius * angle.sin()
+        }
+    });
+    PointCloud::new(points).unwrap()
+}
+
+/// Pseudo-random points in the unit square.
+pub(crate) fn random_cloud(n_points: usize, seed: u64) -> PointCloud {
+    let points = Array2::from_shape_vec((n_points, 2), random_numbers(2 * n_points, seed)).unwrap();
+    PointCloud::new(points).unwrap()
+}
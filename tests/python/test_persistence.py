@@ -43,3 +43,16 @@ def test_square_persistence():
 
     # Then
     # TODO actually test stuff
+
+
+def test_square_persistence_image():
+    # Given
+    square = np.array([[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]])
+    cloud = PointCloud(square)
+
+    # When
+    image = cloud.persistence_image(dim=1, max_dist=10.0, resolution=(10, 5))
+
+    # Then
+    assert image.shape == (5, 10)
+    assert image.sum() > 0.0